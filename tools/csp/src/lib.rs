@@ -0,0 +1,223 @@
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+pub type Var = usize;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    #[error("The constraints can't be satisfied")]
+    Contradiction,
+    #[error("The constraints have more than one solution")]
+    Ambiguous { examples: [Vec<usize>; 2] },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Solver {
+    domains: Vec<BTreeSet<usize>>,
+    all_different: Vec<Vec<Var>>,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn add_variable(&mut self, domain: impl IntoIterator<Item = usize>) -> Var {
+        self.domains.push(domain.into_iter().collect());
+        self.domains.len() - 1
+    }
+
+    pub fn all_different(&mut self, vars: impl IntoIterator<Item = Var>) {
+        let vars: Vec<Var> = vars.into_iter().collect();
+        assert!(
+            vars.iter().all(|var| *var < self.domains.len()),
+            "unknown variable in constraint"
+        );
+        self.all_different.push(vars);
+    }
+
+    pub fn solve(&self) -> Result<Vec<usize>, SolveError> {
+        let mut solutions = self.solutions(2);
+        match solutions.len() {
+            0 => Err(SolveError::Contradiction),
+            1 => Ok(solutions.pop().unwrap()),
+            _ => Err(SolveError::Ambiguous {
+                examples: [solutions.remove(0), solutions.remove(0)],
+            }),
+        }
+    }
+
+    // the domains left once propagation alone can't narrow them further
+    pub fn narrow(&self) -> Result<Vec<BTreeSet<usize>>, SolveError> {
+        let mut domains = self.domains.clone();
+        if self.propagate(&mut domains) {
+            Ok(domains)
+        } else {
+            Err(SolveError::Contradiction)
+        }
+    }
+
+    pub fn solutions(&self, limit: usize) -> Vec<Vec<usize>> {
+        let mut solutions = vec![];
+        if limit > 0 {
+            self.search(self.domains.clone(), limit, &mut solutions);
+        }
+        solutions
+    }
+
+    fn search(
+        &self,
+        mut domains: Vec<BTreeSet<usize>>,
+        limit: usize,
+        solutions: &mut Vec<Vec<usize>>,
+    ) {
+        if !self.propagate(&mut domains) {
+            return;
+        }
+
+        let branch = (0..domains.len())
+            .filter(|var| domains[*var].len() > 1)
+            .min_by_key(|var| domains[*var].len());
+        match branch {
+            None => solutions.push(
+                domains
+                    .iter()
+                    .map(|domain| *domain.iter().next().unwrap())
+                    .collect(),
+            ),
+            Some(var) => {
+                for value in &domains[var] {
+                    if solutions.len() >= limit {
+                        break;
+                    }
+                    let mut guess = domains.clone();
+                    guess[var] = BTreeSet::from([*value]);
+                    self.search(guess, limit, solutions);
+                }
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut [BTreeSet<usize>]) -> bool {
+        if domains.iter().any(BTreeSet::is_empty) {
+            return false;
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for vars in &self.all_different {
+                for &var in vars {
+                    if domains[var].len() != 1 {
+                        continue;
+                    }
+                    let value = *domains[var].iter().next().unwrap();
+                    for &other in vars {
+                        if other != var && domains[other].remove(&value) {
+                            if domains[other].is_empty() {
+                                return false;
+                            }
+                            changed = true;
+                        }
+                    }
+                }
+
+                // n variables can't take distinct values out of less than n values
+                let values: BTreeSet<&usize> = vars.iter().flat_map(|var| &domains[*var]).collect();
+                if values.len() < vars.len() {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distinct(domains: &[&[usize]]) -> Solver {
+        let mut solver = Solver::new();
+        let vars: Vec<Var> = domains
+            .iter()
+            .map(|domain| solver.add_variable(domain.iter().copied()))
+            .collect();
+        solver.all_different(vars);
+        solver
+    }
+
+    #[test]
+    fn test_propagation() {
+        let solver = distinct(&[&[0, 1, 2], &[1], &[1, 2]]);
+        assert_eq!(solver.solve(), Ok(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn test_narrow() {
+        let solver = distinct(&[&[0, 1, 2], &[1], &[1, 2]]);
+        assert_eq!(
+            solver.narrow(),
+            Ok(vec![
+                BTreeSet::from([0]),
+                BTreeSet::from([1]),
+                BTreeSet::from([2])
+            ])
+        );
+        // nothing to propagate without a fixed variable
+        let solver = distinct(&[&[0, 1], &[0, 1], &[0, 1, 2]]);
+        assert_eq!(solver.narrow().unwrap()[2], BTreeSet::from([0, 1, 2]));
+        assert_eq!(
+            distinct(&[&[0], &[0]]).narrow(),
+            Err(SolveError::Contradiction)
+        );
+    }
+
+    #[test]
+    fn test_backtracking() {
+        // no variable is fixed at the start: c only gets 2 once a and b are guessed
+        let solver = distinct(&[&[0, 1], &[0, 1], &[0, 1, 2]]);
+        assert_eq!(solver.solutions(usize::MAX), [vec![0, 1, 2], vec![1, 0, 2]]);
+
+        let solver = distinct(&[&[0, 1], &[0, 2], &[1, 2]]);
+        assert_eq!(solver.solutions(usize::MAX), [vec![0, 2, 1], vec![1, 0, 2]]);
+    }
+
+    #[test]
+    fn test_contradiction() {
+        assert_eq!(
+            distinct(&[&[0], &[0], &[1, 2]]).solve(),
+            Err(SolveError::Contradiction)
+        );
+        // no singles to propagate, but 3 variables share 2 values
+        assert_eq!(
+            distinct(&[&[0, 1], &[0, 1], &[0, 1]]).solve(),
+            Err(SolveError::Contradiction)
+        );
+        assert_eq!(distinct(&[&[]]).solve(), Err(SolveError::Contradiction));
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let solver = distinct(&[&[0, 1], &[0, 1], &[2]]);
+        assert_eq!(
+            solver.solve(),
+            Err(SolveError::Ambiguous {
+                examples: [vec![0, 1, 2], vec![1, 0, 2]]
+            })
+        );
+        assert_eq!(solver.solutions(usize::MAX).len(), 2);
+        assert_eq!(solver.solutions(1), [vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_independent_constraints() {
+        let mut solver = Solver::new();
+        let a = solver.add_variable([0, 1]);
+        let b = solver.add_variable([0]);
+        let c = solver.add_variable([0, 1]);
+        solver.all_different([a, b]);
+        solver.all_different([b, c]);
+        assert_eq!(solver.solve(), Ok(vec![1, 0, 1]));
+    }
+}
@@ -0,0 +1,362 @@
+use std::fmt;
+use thiserror::Error;
+
+const SMALL_HEIGHT: usize = 6;
+const LARGE_HEIGHT: usize = 10;
+
+const SMALL_FONT: [(char, [&str; SMALL_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_FONT: [(char, [&str; LARGE_HEIGHT]); 15] = [
+    (
+        'A',
+        [
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        [
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        [
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        [
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        [
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        [
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        [
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        [
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum OcrError {
+    #[error("Nothing to read")]
+    Empty,
+    #[error("Cannot read letters {height} pixels tall:\n{image}")]
+    UnsupportedHeight { height: usize, image: String },
+    #[error("Unknown glyph:\n{0}")]
+    UnknownGlyph(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    fn from_points(points: &[(usize, usize)]) -> Self {
+        if points.is_empty() {
+            return Bitmap {
+                width: 0,
+                height: 0,
+                pixels: vec![],
+            };
+        }
+
+        let min_x = points.iter().map(|(x, _)| *x).min().unwrap();
+        let max_x = points.iter().map(|(x, _)| *x).max().unwrap();
+        let min_y = points.iter().map(|(_, y)| *y).min().unwrap();
+        let max_y = points.iter().map(|(_, y)| *y).max().unwrap();
+        let width = max_x - min_x + 1;
+        let height = max_y - min_y + 1;
+        let mut pixels = vec![false; width * height];
+        for (x, y) in points {
+            pixels[(y - min_y) * width + (x - min_x)] = true;
+        }
+
+        Bitmap {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn from_rows<'a>(rows: impl IntoIterator<Item = &'a str>) -> Self {
+        let points: Vec<(usize, usize)> = rows
+            .into_iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect();
+        Bitmap::from_points(&points)
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    fn glyphs(&self) -> Vec<Bitmap> {
+        let mut glyphs = vec![];
+        let mut start = None;
+        for x in 0..=self.width {
+            let blank = x == self.width || (0..self.height).all(|y| !self.get(x, y));
+            match (start, blank) {
+                (None, false) => start = Some(x),
+                (Some(from), true) => {
+                    let points: Vec<(usize, usize)> = (from..x)
+                        .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                        .filter(|(x, y)| self.get(*x, *y))
+                        .collect();
+                    glyphs.push(Bitmap::from_points(&points));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        glyphs
+    }
+}
+
+impl fmt::Display for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            let row: String = (0..self.width)
+                .map(|x| if self.get(x, y) { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+fn recognize(image: &Bitmap) -> Result<String, OcrError> {
+    if image.pixels.is_empty() {
+        return Err(OcrError::Empty);
+    }
+
+    let font: Vec<(char, Bitmap)> = match image.height {
+        SMALL_HEIGHT => SMALL_FONT
+            .iter()
+            .map(|(c, rows)| (*c, Bitmap::from_rows(*rows)))
+            .collect(),
+        LARGE_HEIGHT => LARGE_FONT
+            .iter()
+            .map(|(c, rows)| (*c, Bitmap::from_rows(*rows)))
+            .collect(),
+        height => {
+            return Err(OcrError::UnsupportedHeight {
+                height,
+                image: image.to_string(),
+            })
+        }
+    };
+
+    image
+        .glyphs()
+        .into_iter()
+        .map(|glyph| {
+            font.iter()
+                .find(|(_, letter)| *letter == glyph)
+                .map(|(c, _)| *c)
+                .ok_or_else(|| OcrError::UnknownGlyph(glyph.to_string()))
+        })
+        .collect()
+}
+
+pub fn read_points(points: impl IntoIterator<Item = (usize, usize)>) -> Result<String, OcrError> {
+    let points: Vec<(usize, usize)> = points.into_iter().collect();
+    recognize(&Bitmap::from_points(&points))
+}
+
+pub fn read_grid(grid: &str) -> Result<String, OcrError> {
+    recognize(&Bitmap::from_rows(grid.lines()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_font() {
+        let grid = "\
+###..###...##..#..#.####.###..#....###.
+#..#.#..#.#..#.#.#..#....#..#.#....#..#
+#..#.#..#.#....##...###..###..#....#..#
+###..###..#....#.#..#....#..#.#....###.
+#.#..#....#..#.#.#..#....#..#.#....#.#.
+#..#.#.....##..#..#.#....###..####.#..#
+";
+        assert_eq!(read_grid(grid), Ok("RPCKFBLR".to_string()));
+
+        // same letters, as points and shifted around
+        let points = grid.lines().enumerate().flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| (x + 7, y + 3))
+        });
+        assert_eq!(read_points(points), Ok("RPCKFBLR".to_string()));
+
+        let every_letter: String = SMALL_FONT
+            .iter()
+            .map(|(_, rows)| rows.iter().map(|row| format!("{row}..")).collect())
+            .fold(
+                vec![String::new(); SMALL_HEIGHT],
+                |mut grid, glyph: Vec<_>| {
+                    grid.iter_mut()
+                        .zip(glyph)
+                        .for_each(|(line, row)| line.push_str(&row));
+                    grid
+                },
+            )
+            .join("\n");
+        assert_eq!(
+            read_grid(&every_letter),
+            Ok("ABCEFGHIJKLOPRSUYZ".to_string())
+        );
+    }
+
+    #[test]
+    fn test_large_font() {
+        let glyph = |c: char| {
+            LARGE_FONT
+                .iter()
+                .find(|(letter, _)| *letter == c)
+                .unwrap()
+                .1
+        };
+        let grid = "BLEACHER"
+            .chars()
+            .map(glyph)
+            .fold(vec![String::new(); LARGE_HEIGHT], |mut grid, rows| {
+                grid.iter_mut()
+                    .zip(rows)
+                    .for_each(|(line, row)| line.push_str(&format!("{row}..")));
+                grid
+            })
+            .join("\n");
+        assert_eq!(read_grid(&grid), Ok("BLEACHER".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(read_grid("....\n...."), Err(OcrError::Empty));
+
+        let err = read_grid("#####\n#...#\n#...#\n#...#\n#####").unwrap_err();
+        assert_eq!(
+            err,
+            OcrError::UnsupportedHeight {
+                height: 5,
+                image: "#####\n#...#\n#...#\n#...#\n#####\n".to_string()
+            }
+        );
+
+        let err = read_grid("####.#..#\n#....#..#\n#....####\n####.#..#\n...#.#..#\n####.#..#")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown glyph:\n####\n#...\n#...\n####\n...#\n####\n"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// `None` when nothing adds up to `target`, so a returned list is never empty
+pub fn k_sum(values: &[i64], k: usize, target: i64) -> Option<Vec<Vec<i64>>> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+
+    let mut combinations = vec![];
+    search(&sorted, k, target as i128, &mut vec![], &mut combinations);
+    if combinations.is_empty() {
+        None
+    } else {
+        Some(combinations)
+    }
+}
+
+fn sum(values: &[i64]) -> i128 {
+    values.iter().map(|v| *v as i128).sum()
+}
+
+fn search(
+    values: &[i64],
+    k: usize,
+    target: i128,
+    prefix: &mut Vec<i64>,
+    combinations: &mut Vec<Vec<i64>>,
+) {
+    if values.len() < k {
+        return;
+    }
+
+    match k {
+        0 => {
+            if target == 0 {
+                combinations.push(prefix.clone());
+            }
+        }
+        1 => {
+            if let Ok(i) = values.binary_search_by(|v| (*v as i128).cmp(&target)) {
+                prefix.push(values[i]);
+                combinations.push(prefix.clone());
+                prefix.pop();
+            }
+        }
+        2 => two_pointers(values, target, prefix, combinations),
+        _ => {
+            for i in 0..=values.len() - k {
+                if i > 0 && values[i] == values[i - 1] {
+                    continue;
+                }
+                // the smallest sum starting from here is already too big
+                if sum(&values[i..i + k]) > target {
+                    break;
+                }
+                // even the biggest values are not enough
+                if values[i] as i128 + sum(&values[values.len() - k + 1..]) < target {
+                    continue;
+                }
+
+                prefix.push(values[i]);
+                search(
+                    &values[i + 1..],
+                    k - 1,
+                    target - values[i] as i128,
+                    prefix,
+                    combinations,
+                );
+                prefix.pop();
+            }
+        }
+    }
+}
+
+fn two_pointers(values: &[i64], target: i128, prefix: &[i64], combinations: &mut Vec<Vec<i64>>) {
+    let (mut lo, mut hi) = (0, values.len() - 1);
+    while lo < hi {
+        let pair = values[lo] as i128 + values[hi] as i128;
+        if pair < target {
+            lo += 1;
+        } else if pair > target {
+            hi -= 1;
+        } else {
+            let mut combination = prefix.to_vec();
+            combination.extend([values[lo], values[hi]]);
+            combinations.push(combination);
+            // skip the duplicates on both sides
+            while lo < hi && values[lo] == values[lo + 1] {
+                lo += 1;
+            }
+            while lo < hi && values[hi] == values[hi - 1] {
+                hi -= 1;
+            }
+            lo += 1;
+            hi -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn test_example() {
+        assert_eq!(k_sum(&EXAMPLE, 2, 2020), Some(vec![vec![299, 1721]]));
+        assert_eq!(k_sum(&EXAMPLE, 3, 2020), Some(vec![vec![366, 675, 979]]));
+        assert_eq!(k_sum(&EXAMPLE, 4, 2020), None);
+        assert_eq!(k_sum(&EXAMPLE, 1, 979), Some(vec![vec![979]]));
+        assert_eq!(k_sum(&EXAMPLE, 7, 2020), None);
+    }
+
+    #[test]
+    fn test_distinct_combinations() {
+        let values = [1, 0, -1, 0, -2, 2];
+        assert_eq!(
+            k_sum(&values, 4, 0),
+            Some(vec![
+                vec![-2, -1, 1, 2],
+                vec![-2, 0, 0, 2],
+                vec![-1, 0, 0, 1]
+            ])
+        );
+        assert_eq!(k_sum(&[2, 2, 2, 2, 2], 4, 8), Some(vec![vec![2, 2, 2, 2]]));
+        assert_eq!(k_sum(&[1, 1, 3, 3], 2, 4), Some(vec![vec![1, 3]]));
+        assert_eq!(k_sum(&[1, 2], 0, 0), Some(vec![vec![]]));
+    }
+
+    #[test]
+    fn test_no_overflow() {
+        let values = [i64::MAX, i64::MAX, i64::MIN, 1];
+        assert_eq!(
+            k_sum(&values, 3, i64::MAX - 1),
+            Some(vec![vec![i64::MIN, i64::MAX, i64::MAX]])
+        );
+    }
+}
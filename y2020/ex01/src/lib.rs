@@ -1,58 +1,80 @@
-use std::collections::HashSet;
-
-pub fn part1(values: Vec<u32>) -> u32 {
-    let idx: HashSet<u32> = values.iter().cloned().collect();
-    for v in values {
-        let complement = 2020 - v;
-        if idx.contains(&complement) {
-            return v * complement;
-        }
-    }
-    panic!("Solution not found");
+mod ksum;
+
+pub use ksum::k_sum;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ExpenseError {
+    #[error("Line {line}: invalid expense '{found}'")]
+    InvalidExpense { line: usize, found: String },
+    #[error("The product of the expenses does not fit in an i64")]
+    Overflow,
 }
 
-pub fn part2(values: Vec<u32>) -> u32 {
-    let idx: HashSet<u32> = values.iter().cloned().collect();
-    for (i, v) in values.iter().enumerate() {
-        for k in values.iter().skip(i) {
-            if v + k > 2020 {
-                continue;
-            }
-            let complement = 2020 - (v + k);
-            if idx.contains(&complement) {
-                return complement * v * k;
-            }
-        }
-    }
-    panic!("Solution not found");
+pub fn parse(input: &str) -> Result<Vec<i64>, ExpenseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            l.parse::<i64>().map_err(|_| ExpenseError::InvalidExpense {
+                line: i + 1,
+                found: l.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn expense_product(input: &str, k: usize) -> Result<Option<i64>, ExpenseError> {
+    let combinations = match k_sum(&parse(input)?, k, 2020) {
+        Some(combinations) => combinations,
+        None => return Ok(None),
+    };
+    combinations[0]
+        .iter()
+        .try_fold(1i64, |product, value| product.checked_mul(*value))
+        .map(Some)
+        .ok_or(ExpenseError::Overflow)
+}
+
+pub fn part1(input: &str) -> i64 {
+    expense_product(input, 2)
+        .unwrap()
+        .expect("Solution not found")
+}
+
+pub fn part2(input: &str) -> i64 {
+    expense_product(input, 3)
+        .unwrap()
+        .expect("Solution not found")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("1721\n97x\n366"),
+            Err(ExpenseError::InvalidExpense {
+                line: 2,
+                found: "97x".to_string()
+            })
+        );
+        let input = "4294967296\n-4294965276";
+        assert_eq!(expense_product(input, 2), Err(ExpenseError::Overflow));
+        assert_eq!(expense_product("1\n2", 2), Ok(None));
+    }
+
     #[test]
     fn part_1() {
         let input = include_str!("../input.txt");
-        let values: Vec<u32> = input
-            .lines()
-            .map(|l| {
-                l.parse::<u32>()
-                    .unwrap_or_else(|_| panic!("Cannot convert line '{:?}' to u32", l))
-            })
-            .collect();
-        assert_eq!(part1(values), 866436);
+        assert_eq!(part1(input), 866436);
     }
 
     #[test]
     fn part_2() {
         let input = include_str!("../input.txt");
-        let values: Vec<u32> = input
-            .lines()
-            .map(|l| {
-                l.parse::<u32>()
-                    .unwrap_or_else(|_| panic!("Cannot convert line '{:?}' to u32", l))
-            })
-            .collect();
-        assert_eq!(part2(values), 276650720);
+        assert_eq!(part2(input), 276650720);
     }
 }
@@ -0,0 +1,63 @@
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Record(Vec<(String, String)>);
+
+impl Record {
+    pub fn parse(s: &str) -> Self {
+        Record(
+            s.split_whitespace()
+                .map(|pair| match pair.split_once(':') {
+                    Some((key, value)) => (key.to_string(), value.to_string()),
+                    // a key with no value at all
+                    None => (pair.to_string(), String::new()),
+                })
+                .collect(),
+        )
+    }
+
+    pub fn parse_all(input: &str) -> Vec<Self> {
+        let mut records = vec![];
+        let mut current = String::new();
+        for line in input.lines() {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    records.push(Record::parse(&current));
+                    current.clear();
+                }
+            } else {
+                current.push(' ');
+                current.push_str(line);
+            }
+        }
+        if !current.is_empty() {
+            records.push(Record::parse(&current));
+        }
+        records
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(key, _)| key.as_str())
+    }
+
+    pub fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.0
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_all() {
+        let records = Record::parse_all("a:1 b:2\nc:3\n\n\n  \nd:4\r\ne\n");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(records[0].values("c").collect::<Vec<_>>(), ["3"]);
+        assert_eq!(records[1].keys().collect::<Vec<_>>(), ["d", "e"]);
+        assert_eq!(records[1].values("e").collect::<Vec<_>>(), [""]);
+        assert_eq!(records[1].values("a").count(), 0);
+    }
+}
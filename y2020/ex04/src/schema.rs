@@ -0,0 +1,365 @@
+use crate::record::Record;
+use crate::validators::{
+    EnumValidator, RangeValidator, RegexValidator, UnitRangeValidator, Validator,
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SchemaError {
+    #[error("Invalid TOML schema: {0}")]
+    Toml(toml::de::Error),
+    #[error("Invalid JSON schema: {0}")]
+    Json(serde_json::Error),
+    #[error("Invalid regex for field '{field}': {source}")]
+    InvalidRegex { field: String, source: regex::Error },
+    #[error("Range for field '{field}' has min greater than max")]
+    EmptyRange { field: String },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaDef {
+    #[serde(default)]
+    strict: bool,
+    fields: BTreeMap<String, FieldDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldDef {
+    #[serde(default = "default_required")]
+    required: bool,
+    rule: Option<RuleDef>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+enum RuleDef {
+    Range { min: i64, max: i64 },
+    Regex { pattern: String },
+    Units { units: BTreeMap<String, [i64; 2]> },
+    Enum { values: Vec<String> },
+}
+
+struct Field {
+    name: String,
+    required: bool,
+    validator: Option<Box<dyn Validator>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Missing,
+    Invalid(String),
+    Unknown,
+    Duplicated,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Missing => write!(f, "missing"),
+            Failure::Invalid(reason) => write!(f, "{}", reason),
+            Failure::Unknown => write!(f, "not part of the schema"),
+            Failure::Duplicated => write!(f, "specified more than once"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub failure: Failure,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub record: usize,
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_valid() {
+            return write!(f, "record {}: valid", self.record);
+        }
+        write!(f, "record {}: invalid", self.record)?;
+        for error in &self.errors {
+            write!(f, "\n  {}: {}", error.field, error.failure)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Schema {
+    strict: bool,
+    fields: Vec<Field>,
+}
+
+impl Schema {
+    pub fn from_toml(s: &str) -> Result<Self, SchemaError> {
+        Self::compile(toml::from_str(s).map_err(SchemaError::Toml)?)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, SchemaError> {
+        Self::compile(serde_json::from_str(s).map_err(SchemaError::Json)?)
+    }
+
+    pub fn passport() -> Self {
+        Self::from_toml(include_str!("../passport.toml")).unwrap()
+    }
+
+    pub fn presence_only(self) -> Self {
+        Schema {
+            fields: self
+                .fields
+                .into_iter()
+                .map(|field| Field {
+                    validator: None,
+                    ..field
+                })
+                .collect(),
+            ..self
+        }
+    }
+
+    fn compile(def: SchemaDef) -> Result<Self, SchemaError> {
+        let mut fields = vec![];
+        for (name, field) in def.fields {
+            let validator: Option<Box<dyn Validator>> = match field.rule {
+                None => None,
+                Some(RuleDef::Range { min, max }) => {
+                    check_range(&name, min, max)?;
+                    Some(Box::new(RangeValidator::new(min..=max)))
+                }
+                Some(RuleDef::Regex { pattern }) => {
+                    let regex =
+                        Regex::new(&pattern).map_err(|source| SchemaError::InvalidRegex {
+                            field: name.clone(),
+                            source,
+                        })?;
+                    Some(Box::new(RegexValidator::new(regex)))
+                }
+                Some(RuleDef::Units { units }) => {
+                    let mut ranges = BTreeMap::new();
+                    for (unit, [min, max]) in units {
+                        check_range(&name, min, max)?;
+                        ranges.insert(unit, min..=max);
+                    }
+                    Some(Box::new(UnitRangeValidator::new(ranges)))
+                }
+                Some(RuleDef::Enum { values }) => Some(Box::new(EnumValidator::new(values))),
+            };
+            fields.push(Field {
+                name,
+                required: field.required,
+                validator,
+            });
+        }
+
+        Ok(Schema {
+            strict: def.strict,
+            fields,
+        })
+    }
+
+    pub fn validate(&self, index: usize, record: &Record) -> ValidationReport {
+        let mut errors = vec![];
+        let error = |field: &str, failure| FieldError {
+            field: field.to_string(),
+            failure,
+        };
+
+        for field in &self.fields {
+            let mut values = record.values(&field.name);
+            match (values.next(), values.next()) {
+                (None, _) if field.required => errors.push(error(&field.name, Failure::Missing)),
+                (None, _) => {}
+                (Some(_), Some(_)) => errors.push(error(&field.name, Failure::Duplicated)),
+                (Some(value), None) => {
+                    if let Some(validator) = &field.validator {
+                        if let Err(reason) = validator.validate(value) {
+                            errors.push(error(&field.name, Failure::Invalid(reason)));
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.strict {
+            for key in record.keys() {
+                if self.fields.iter().all(|field| field.name != key) {
+                    errors.push(error(key, Failure::Unknown));
+                }
+            }
+        }
+
+        ValidationReport {
+            record: index,
+            errors,
+        }
+    }
+
+    pub fn validate_all(&self, input: &str) -> Vec<ValidationReport> {
+        Record::parse_all(input)
+            .iter()
+            .enumerate()
+            .map(|(index, record)| self.validate(index, record))
+            .collect()
+    }
+}
+
+fn check_range(field: &str, min: i64, max: i64) -> Result<(), SchemaError> {
+    if min > max {
+        return Err(SchemaError::EmptyRange {
+            field: field.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_passports() {
+        let input = "eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378 byr:2007";
+        let reports = Schema::passport().validate_all(input);
+        assert_eq!(reports.len(), 4);
+        assert!(reports.iter().all(|report| !report.is_valid()));
+        assert_eq!(
+            reports[0].to_string(),
+            "record 0: invalid
+  eyr: 1972 is not between 2020 and 2030
+  hgt: '170' is not a number followed by a unit
+  pid: '186cm' does not match /^\\d{9}$/"
+        );
+        assert_eq!(reports[3].errors.len(), 7);
+    }
+
+    #[test]
+    fn test_valid_passports() {
+        let input = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+";
+        let reports = Schema::passport().validate_all(input);
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(ValidationReport::is_valid));
+        assert_eq!(reports[1].to_string(), "record 1: valid");
+    }
+
+    #[test]
+    fn test_missing_and_presence_only() {
+        let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183";
+        let report = &Schema::passport().validate_all(input)[0];
+        assert_eq!(
+            report.errors,
+            [FieldError {
+                field: "hgt".to_string(),
+                failure: Failure::Invalid("'183' is not a number followed by a unit".to_string())
+            }]
+        );
+
+        let report = &Schema::passport().presence_only().validate_all(input)[0];
+        assert!(report.is_valid());
+
+        let report = &Schema::passport().validate_all("byr:1937 cid:147")[0];
+        let missing: Vec<&str> = report
+            .errors
+            .iter()
+            .filter(|e| e.failure == Failure::Missing)
+            .map(|e| e.field.as_str())
+            .collect();
+        assert_eq!(missing, ["ecl", "eyr", "hcl", "hgt", "iyr", "pid"]);
+    }
+
+    #[test]
+    fn test_json_strict_schema() {
+        let schema = Schema::from_json(
+            r#"{
+                "strict": true,
+                "fields": {
+                    "name": { "rule": { "type": "regex", "pattern": "^[a-z]+$" } },
+                    "age": { "rule": { "type": "range", "min": 0, "max": 150 } },
+                    "role": { "required": false, "rule": { "type": "enum", "values": ["admin", "user"] } }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let reports = schema.validate_all("name:bob age:42\n\nname:bob age:42 role:root x:1 age:3");
+        assert!(reports[0].is_valid());
+        assert_eq!(
+            reports[1].errors,
+            [
+                FieldError {
+                    field: "age".to_string(),
+                    failure: Failure::Duplicated
+                },
+                FieldError {
+                    field: "role".to_string(),
+                    failure: Failure::Invalid("'root' is not one of admin, user".to_string())
+                },
+                FieldError {
+                    field: "x".to_string(),
+                    failure: Failure::Unknown
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        let err = Schema::from_toml("[fields.a]\nrule = { type = \"regex\", pattern = \"(\" }")
+            .err()
+            .unwrap();
+        assert!(matches!(err, SchemaError::InvalidRegex { field, .. } if field == "a"));
+
+        let err = Schema::from_toml("[fields.a]\nrule = { type = \"range\", min = 3, max = 1 }")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Range for field 'a' has min greater than max"
+        );
+
+        let err = Schema::from_toml("[fields.a]\nrule = { type = \"wat\" }")
+            .err()
+            .unwrap();
+        assert!(matches!(err, SchemaError::Toml(_)));
+
+        let err = Schema::from_json("{").err().unwrap();
+        assert!(matches!(err, SchemaError::Json(_)));
+    }
+}
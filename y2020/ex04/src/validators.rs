@@ -1,29 +1,41 @@
 use regex::Regex;
+use std::collections::BTreeMap;
 use std::ops::RangeInclusive;
 
 pub trait Validator {
-    fn validate(&self, value: &str) -> bool;
+    fn validate(&self, value: &str) -> Result<(), String>;
 }
 
-pub struct U16RangeValidator {
-    range: RangeInclusive<u16>,
+// e.g. byr (Birth Year) - four digits; at least 1920 and at most 2002.
+pub struct RangeValidator {
+    range: RangeInclusive<i64>,
 }
 
-impl U16RangeValidator {
-    pub fn new(range: RangeInclusive<u16>) -> Self {
-        U16RangeValidator { range }
+impl RangeValidator {
+    pub fn new(range: RangeInclusive<i64>) -> Self {
+        RangeValidator { range }
     }
 }
 
-impl Validator for U16RangeValidator {
-    fn validate(&self, value: &str) -> bool {
-        if let Ok(year) = value.parse::<u16>() {
-            return self.range.contains(&year);
+impl Validator for RangeValidator {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        let num: i64 = value
+            .parse()
+            .map_err(|_| format!("'{}' is not a number", value))?;
+        if self.range.contains(&num) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} is not between {} and {}",
+                num,
+                self.range.start(),
+                self.range.end()
+            ))
         }
-        false
     }
 }
 
+// e.g. hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
 pub struct RegexValidator {
     regex: Regex,
 }
@@ -35,72 +47,143 @@ impl RegexValidator {
 }
 
 impl Validator for RegexValidator {
-    fn validate(&self, value: &str) -> bool {
-        self.regex.is_match(value)
+    fn validate(&self, value: &str) -> Result<(), String> {
+        if self.regex.is_match(value) {
+            Ok(())
+        } else {
+            Err(format!("'{}' does not match /{}/", value, self.regex))
+        }
     }
 }
 
-// byr (Birth Year) - four digits; at least 1920 and at most 2002.
-pub fn create_byr_validator() -> U16RangeValidator {
-    U16RangeValidator::new(1920..=2002)
-}
-
-// iyr (Issue Year) - four digits; at least 2010 and at most 2020.
-pub fn create_iyr_validator() -> U16RangeValidator {
-    U16RangeValidator::new(2010..=2020)
-}
-
-// eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
-pub fn create_eyr_validator() -> U16RangeValidator {
-    U16RangeValidator::new(2020..=2030)
-}
-
-// hgt (Height) - a number followed by either cm or in.
+// e.g. hgt (Height) - a number followed by either cm or in.
 //   If cm, the number must be at least 150 and at most 193.
 //   If in, the number must be at least 59 and at most 76.
-pub struct HgtValidator {
+pub struct UnitRangeValidator {
     regex: Regex,
+    units: BTreeMap<String, RangeInclusive<i64>>,
 }
 
-impl HgtValidator {
-    pub fn new() -> Self {
-        let regex = Regex::new(r"^(\d+)(in|cm)$").unwrap();
-        HgtValidator { regex }
+impl UnitRangeValidator {
+    pub fn new(units: BTreeMap<String, RangeInclusive<i64>>) -> Self {
+        let regex = Regex::new(r"^(-?\d+)(\D+)$").unwrap();
+        UnitRangeValidator { regex, units }
     }
 }
 
-impl Validator for HgtValidator {
-    fn validate(&self, value: &str) -> bool {
-        if let Some(captures) = self.regex.captures(value) {
-            let unit = captures.get(2).unwrap().as_str();
-
-            if let Ok(num) = captures.get(1).unwrap().as_str().parse::<u16>() {
-                return (unit == "in" && (59..=76).contains(&num))
-                    || (unit == "cm" && (150..=193).contains(&num));
-            }
+impl Validator for UnitRangeValidator {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        let captures = self
+            .regex
+            .captures(value)
+            .ok_or_else(|| format!("'{}' is not a number followed by a unit", value))?;
+        let unit = captures.get(2).unwrap().as_str();
+        let range = self.units.get(unit).ok_or_else(|| {
+            let units: Vec<&str> = self.units.keys().map(String::as_str).collect();
+            format!("unit '{}' is not one of {}", unit, units.join(", "))
+        })?;
+
+        let num: i64 = captures
+            .get(1)
+            .unwrap()
+            .as_str()
+            .parse()
+            .map_err(|_| format!("'{}' is too big", value))?;
+        if range.contains(&num) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{}{} is not between {}{} and {}{}",
+                num,
+                unit,
+                range.start(),
+                unit,
+                range.end(),
+                unit
+            ))
         }
-        false
     }
 }
 
-pub fn create_hgt_validator() -> HgtValidator {
-    HgtValidator::new()
+// e.g. ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
+pub struct EnumValidator {
+    values: Vec<String>,
 }
 
-// hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
-pub fn create_hcl_validator() -> RegexValidator {
-    let regex = Regex::new(r"^#[0-9a-fA-F]{6}$").unwrap();
-    RegexValidator::new(regex)
+impl EnumValidator {
+    pub fn new(values: Vec<String>) -> Self {
+        EnumValidator { values }
+    }
 }
 
-// ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
-pub fn create_ecl_validator() -> RegexValidator {
-    let regex = Regex::new(r"^(amb|blu|brn|gry|grn|hzl|oth)$").unwrap();
-    RegexValidator::new(regex)
+impl Validator for EnumValidator {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        if self.values.iter().any(|v| v == value) {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{}' is not one of {}",
+                value,
+                self.values.join(", ")
+            ))
+        }
+    }
 }
 
-// pid (Passport ID) - a nine-digit number, including leading zeroes.
-pub fn create_pid_validator() -> RegexValidator {
-    let regex = Regex::new(r"^\d{9}$").unwrap();
-    RegexValidator::new(regex)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        let validator = RangeValidator::new(1920..=2002);
+        assert_eq!(validator.validate("2002"), Ok(()));
+        assert_eq!(
+            validator.validate("2003"),
+            Err("2003 is not between 1920 and 2002".to_string())
+        );
+        assert_eq!(
+            validator.validate("abc"),
+            Err("'abc' is not a number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unit_range() {
+        let units = vec![("cm".to_string(), 150..=193), ("in".to_string(), 59..=76)]
+            .into_iter()
+            .collect();
+        let validator = UnitRangeValidator::new(units);
+        assert_eq!(validator.validate("60in"), Ok(()));
+        assert_eq!(validator.validate("190cm"), Ok(()));
+        assert_eq!(
+            validator.validate("190in"),
+            Err("190in is not between 59in and 76in".to_string())
+        );
+        assert_eq!(
+            validator.validate("190"),
+            Err("'190' is not a number followed by a unit".to_string())
+        );
+        assert_eq!(
+            validator.validate("190mm"),
+            Err("unit 'mm' is not one of cm, in".to_string())
+        );
+    }
+
+    #[test]
+    fn test_regex_and_enum() {
+        let validator = RegexValidator::new(Regex::new(r"^\d{9}$").unwrap());
+        assert_eq!(validator.validate("000000001"), Ok(()));
+        assert_eq!(
+            validator.validate("0123456789"),
+            Err(r"'0123456789' does not match /^\d{9}$/".to_string())
+        );
+
+        let validator = EnumValidator::new(vec!["amb".to_string(), "blu".to_string()]);
+        assert_eq!(validator.validate("blu"), Ok(()));
+        assert_eq!(
+            validator.validate("wat"),
+            Err("'wat' is not one of amb, blu".to_string())
+        );
+    }
 }
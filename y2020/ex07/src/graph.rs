@@ -0,0 +1,361 @@
+use regex::Regex;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+    str::FromStr,
+};
+use thiserror::Error;
+
+lazy_static! {
+    static ref LINE_REGEX: Regex = Regex::new(
+        r"^(\w+\s\w+) bags contain ((?:(?:\d+) (?:\w+\s\w+) bags?(?:[,.]\s?))+|no other bags.)$"
+    )
+    .unwrap();
+    static ref BAGS_REGEX: Regex = Regex::new(r"(\d+) (\w+\s\w+) bags?").unwrap();
+}
+
+pub type BagId = usize;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    #[error("Invalid rule on line {line}: '{rule}'")]
+    InvalidRule { line: usize, rule: String },
+    #[error("Unknown bag '{0}'")]
+    UnknownBag(String),
+    #[error("Bags contain each other: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("The number of bags inside '{0}' does not fit in 64 bits")]
+    Overflow(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mark {
+    Visiting,
+    Done(u64),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BagGraph {
+    names: Vec<String>,
+    ids: HashMap<String, BagId>,
+    children: Vec<Vec<(u64, BagId)>>,
+    parents: Vec<Vec<BagId>>,
+}
+
+impl FromStr for BagGraph {
+    type Err = GraphError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut graph = BagGraph::default();
+        for (i, line) in input.lines().enumerate() {
+            let invalid = || GraphError::InvalidRule {
+                line: i + 1,
+                rule: line.to_string(),
+            };
+            let capture = LINE_REGEX.captures(line).ok_or_else(invalid)?;
+            let bag = graph.intern(&capture[1]);
+            for captured_bags in BAGS_REGEX.captures_iter(&capture[2]) {
+                let count = captured_bags[1].parse().map_err(|_| invalid())?;
+                let child = graph.intern(&captured_bags[2]);
+                graph.children[bag].push((count, child));
+                graph.parents[child].push(bag);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl BagGraph {
+    fn intern(&mut self, name: &str) -> BagId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.children.push(vec![]);
+        self.parents.push(vec![]);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Result<BagId, GraphError> {
+        self.ids
+            .get(name)
+            .copied()
+            .ok_or_else(|| GraphError::UnknownBag(name.to_string()))
+    }
+
+    pub fn name(&self, id: BagId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn containers(&self, name: &str) -> Result<usize, GraphError> {
+        let start = self.id(name)?;
+        let mut visited = vec![false; self.len()];
+        let mut check_queue = VecDeque::from([start]);
+        visited[start] = true;
+
+        let mut count = 0;
+        while let Some(bag) = check_queue.pop_front() {
+            for &parent in &self.parents[bag] {
+                if !visited[parent] {
+                    visited[parent] = true;
+                    count += 1;
+                    check_queue.push_back(parent);
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    pub fn contained(&self, name: &str) -> Result<u64, GraphError> {
+        let mut marks = vec![None; self.len()];
+        self.count_inside(self.id(name)?, &mut marks, &mut vec![])
+    }
+
+    fn count_inside(
+        &self,
+        bag: BagId,
+        marks: &mut [Option<Mark>],
+        path: &mut Vec<BagId>,
+    ) -> Result<u64, GraphError> {
+        match marks[bag] {
+            Some(Mark::Done(count)) => return Ok(count),
+            Some(Mark::Visiting) => return Err(GraphError::Cycle(self.cycle(path, bag))),
+            None => {}
+        }
+
+        marks[bag] = Some(Mark::Visiting);
+        path.push(bag);
+        let overflow = || GraphError::Overflow(self.names[bag].clone());
+        let mut total: u64 = 0;
+        for &(count, child) in &self.children[bag] {
+            let inside = self.count_inside(child, marks, path)?;
+            // every child bag, plus everything inside it
+            let bags = inside
+                .checked_add(1)
+                .and_then(|bags| bags.checked_mul(count))
+                .ok_or_else(overflow)?;
+            total = total.checked_add(bags).ok_or_else(overflow)?;
+        }
+        path.pop();
+        marks[bag] = Some(Mark::Done(total));
+        Ok(total)
+    }
+
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut done = vec![false; self.len()];
+        (0..self.len()).find_map(|bag| self.walk(bag, &mut done, &mut vec![]).err())
+    }
+
+    fn walk(
+        &self,
+        bag: BagId,
+        done: &mut [bool],
+        path: &mut Vec<BagId>,
+    ) -> Result<(), Vec<String>> {
+        if done[bag] {
+            return Ok(());
+        }
+        if path.contains(&bag) {
+            return Err(self.cycle(path, bag));
+        }
+        path.push(bag);
+        for &(_, child) in &self.children[bag] {
+            self.walk(child, done, path)?;
+        }
+        path.pop();
+        done[bag] = true;
+        Ok(())
+    }
+
+    fn cycle(&self, path: &[BagId], bag: BagId) -> Vec<String> {
+        let start = path.iter().position(|b| *b == bag).unwrap();
+        path[start..]
+            .iter()
+            .chain(&[bag])
+            .map(|b| self.names[*b].clone())
+            .collect()
+    }
+
+    pub fn shortest_chain(&self, from: &str, to: &str) -> Result<Option<Vec<&str>>, GraphError> {
+        let (from, to) = (self.id(from)?, self.id(to)?);
+        let mut previous: Vec<Option<BagId>> = vec![None; self.len()];
+        let mut check_queue = VecDeque::from([from]);
+        previous[from] = Some(from);
+
+        while let Some(bag) = check_queue.pop_front() {
+            if bag == to {
+                let mut chain = vec![self.name(to)];
+                let mut bag = to;
+                while bag != from {
+                    bag = previous[bag].unwrap();
+                    chain.push(self.name(bag));
+                }
+                chain.reverse();
+                return Ok(Some(chain));
+            }
+            for &(_, child) in &self.children[bag] {
+                if previous[child].is_none() {
+                    previous[child] = Some(bag);
+                    check_queue.push_back(child);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph bags {\n");
+        for name in &self.names {
+            writeln!(dot, "    \"{}\";", name).unwrap();
+        }
+        for (bag, children) in self.children.iter().enumerate() {
+            for (count, child) in children {
+                writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [label={}];",
+                    self.names[bag], self.names[*child], count
+                )
+                .unwrap();
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    #[test]
+    fn test_example() {
+        let graph: BagGraph = EXAMPLE.parse().unwrap();
+        assert_eq!(graph.len(), 9);
+        assert_eq!(graph.containers("shiny gold"), Ok(4));
+        assert_eq!(graph.contained("shiny gold"), Ok(32));
+        assert_eq!(graph.contained("faded blue"), Ok(0));
+        assert_eq!(graph.find_cycle(), None);
+        assert_eq!(
+            graph.contained("plaid red"),
+            Err(GraphError::UnknownBag("plaid red".to_string()))
+        );
+
+        let input = "shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.";
+        let graph: BagGraph = input.parse().unwrap();
+        assert_eq!(graph.contained("shiny gold"), Ok(126));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let input = "light red bags contain 1 bright white bag.
+bright white bags contain 2 muted yellow bags.
+muted yellow bags contain 1 light red bag, 3 faded blue bags.
+faded blue bags contain no other bags.";
+        let graph: BagGraph = input.parse().unwrap();
+        let err = graph.contained("bright white").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Bags contain each other: bright white -> muted yellow -> light red -> bright white"
+        );
+        assert_eq!(
+            graph.find_cycle(),
+            Some(vec![
+                "light red".to_string(),
+                "bright white".to_string(),
+                "muted yellow".to_string(),
+                "light red".to_string()
+            ])
+        );
+        // the bags outside the cycle can still be counted
+        assert_eq!(graph.contained("faded blue"), Ok(0));
+        assert_eq!(graph.containers("faded blue"), Ok(3));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "a red bags contain 99999 a blue bags.
+a blue bags contain 99999 a green bags.
+a green bags contain 99999 a white bags.
+a white bags contain 99999 a black bags.
+a black bags contain no other bags.";
+        let graph: BagGraph = input.parse().unwrap();
+        assert_eq!(graph.contained("a green"), Ok(99999 + 99999 * 99999));
+        assert_eq!(
+            graph.contained("a red"),
+            Err(GraphError::Overflow("a red".to_string()))
+        );
+        assert_eq!(graph.find_cycle(), None);
+    }
+
+    #[test]
+    fn test_shortest_chain() {
+        let graph: BagGraph = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            graph.shortest_chain("light red", "dotted black"),
+            Ok(Some(vec![
+                "light red",
+                "bright white",
+                "shiny gold",
+                "dark olive",
+                "dotted black"
+            ]))
+        );
+        assert_eq!(
+            graph.shortest_chain("muted yellow", "faded blue"),
+            Ok(Some(vec!["muted yellow", "faded blue"]))
+        );
+        assert_eq!(graph.shortest_chain("faded blue", "light red"), Ok(None));
+        assert_eq!(
+            graph.shortest_chain("shiny gold", "shiny gold"),
+            Ok(Some(vec!["shiny gold"]))
+        );
+    }
+
+    #[test]
+    fn test_dot_and_errors() {
+        let graph: BagGraph = "light red bags contain 1 bright white bag, 2 muted yellow bags."
+            .parse()
+            .unwrap();
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph bags {
+    "light red";
+    "bright white";
+    "muted yellow";
+    "light red" -> "bright white" [label=1];
+    "light red" -> "muted yellow" [label=2];
+}"#
+        );
+
+        let err = "light red bags contain 1 bright white bag.\nwhat?"
+            .parse::<BagGraph>()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid rule on line 2: 'what?'");
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::BTreeMap, str::FromStr};
+use thiserror::Error;
+
+pub const DEFAULT_MAX_STEP: u64 = 3;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ChainError {
+    #[error("Invalid joltage '{rating}' on line {line}")]
+    InvalidJoltage { line: usize, rating: String },
+    #[error("More than one adapter rated {0} jolts")]
+    DuplicateAdapter(u64),
+    #[error("Cannot connect {to} jolts to {from} jolts")]
+    Gap { from: u64, to: u64 },
+    #[error("Too many arrangements to count")]
+    Overflow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterChain {
+    adapters: Vec<u64>,
+    max_step: u64,
+}
+
+impl FromStr for AdapterChain {
+    type Err = ChainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut adapters = s
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                l.trim().parse().map_err(|_| ChainError::InvalidJoltage {
+                    line: i + 1,
+                    rating: l.to_string(),
+                })
+            })
+            .collect::<Result<Vec<u64>, _>>()?;
+        adapters.sort_unstable();
+        if let Some(pair) = adapters.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(ChainError::DuplicateAdapter(pair[0]));
+        }
+
+        Ok(AdapterChain {
+            adapters,
+            max_step: DEFAULT_MAX_STEP,
+        })
+    }
+}
+
+impl AdapterChain {
+    pub fn with_max_step(self, max_step: u64) -> Self {
+        AdapterChain { max_step, ..self }
+    }
+
+    pub fn device(&self) -> u64 {
+        self.adapters.last().copied().unwrap_or(0) + self.max_step
+    }
+
+    pub fn joltages(&self) -> impl Iterator<Item = u64> + '_ {
+        std::iter::once(0)
+            .chain(self.adapters.iter().copied())
+            .chain(std::iter::once(self.device()))
+    }
+
+    pub fn differences(&self) -> Result<BTreeMap<u64, usize>, ChainError> {
+        let mut histogram = BTreeMap::new();
+        let mut from = 0;
+        for to in self.joltages().skip(1) {
+            if to - from > self.max_step || to == from {
+                return Err(ChainError::Gap { from, to });
+            }
+            *histogram.entry(to - from).or_insert(0) += 1;
+            from = to;
+        }
+        Ok(histogram)
+    }
+
+    pub fn arrangements(&self) -> Result<u128, ChainError> {
+        let joltages: Vec<u64> = self.joltages().collect();
+        // ways[i] is the number of paths from the outlet to joltages[i], which is the sum
+        // over the window of previous joltages within reach
+        let mut ways: Vec<u128> = vec![1];
+        let mut window_start = 0;
+        let mut window_sum: u128 = 1;
+        for i in 1..joltages.len() {
+            while joltages[i] - joltages[window_start] > self.max_step {
+                window_sum -= ways[window_start];
+                window_start += 1;
+            }
+            if window_start == i {
+                return Err(ChainError::Gap {
+                    from: joltages[i - 1],
+                    to: joltages[i],
+                });
+            }
+            ways.push(window_sum);
+            window_sum = window_sum
+                .checked_add(ways[i])
+                .ok_or(ChainError::Overflow)?;
+        }
+        Ok(ways[ways.len() - 1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4";
+    const LARGE: &str = "28\n33\n18\n42\n31\n14\n46\n20\n48\n47\n24\n23\n49\n45\n19\n38\n39\n11\n1\n32\n25\n35\n8\n17\n7\n9\n4\n2\n34\n10\n3";
+
+    #[test]
+    fn test_examples() {
+        let chain: AdapterChain = SMALL.parse().unwrap();
+        assert_eq!(chain.device(), 22);
+        assert_eq!(chain.differences(), Ok(BTreeMap::from([(1, 7), (3, 5)])));
+        assert_eq!(chain.arrangements(), Ok(8));
+
+        let chain: AdapterChain = LARGE.parse().unwrap();
+        assert_eq!(chain.differences(), Ok(BTreeMap::from([(1, 22), (3, 10)])));
+        assert_eq!(chain.arrangements(), Ok(19208));
+    }
+
+    #[test]
+    fn test_two_jolt_gaps() {
+        // 0 2 3 5 (8): either the 2 or the 3 can be skipped, but not both
+        let chain: AdapterChain = "2\n3\n5".parse().unwrap();
+        assert_eq!(
+            chain.differences(),
+            Ok(BTreeMap::from([(1, 1), (2, 2), (3, 1)]))
+        );
+        assert_eq!(chain.arrangements(), Ok(3));
+
+        // every even joltage: each adapter is required, as it is the only one in reach
+        let evens: String = (1..=50).map(|i| format!("{}\n", i * 2)).collect();
+        let chain: AdapterChain = evens.parse().unwrap();
+        assert_eq!(chain.arrangements(), Ok(1));
+    }
+
+    #[test]
+    fn test_max_step() {
+        let chain: AdapterChain = SMALL.parse::<AdapterChain>().unwrap().with_max_step(1);
+        assert_eq!(chain.device(), 20);
+        assert_eq!(chain.differences(), Err(ChainError::Gap { from: 1, to: 4 }));
+        assert_eq!(
+            chain.arrangements(),
+            Err(ChainError::Gap { from: 1, to: 4 })
+        );
+
+        // with steps up to 4, 1..=n has tetranacci-many arrangements
+        let chain: AdapterChain = "1\n2\n3\n4\n5"
+            .parse::<AdapterChain>()
+            .unwrap()
+            .with_max_step(4);
+        assert_eq!(chain.arrangements(), Ok(15));
+    }
+
+    #[test]
+    fn test_long_chains() {
+        // 0..=n with steps up to 3 is counted by the tribonacci numbers, which soon
+        // outgrow u64 and then u128
+        let ones: String = (1..=100).map(|i| format!("{}\n", i)).collect();
+        let chain: AdapterChain = ones.parse().unwrap();
+        assert_eq!(chain.arrangements(), Ok(180396380815100901214157639));
+
+        let ones: String = (1..=200).map(|i| format!("{}\n", i)).collect();
+        let chain: AdapterChain = ones.parse().unwrap();
+        assert_eq!(chain.arrangements(), Err(ChainError::Overflow));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "1\nx".parse::<AdapterChain>(),
+            Err(ChainError::InvalidJoltage {
+                line: 2,
+                rating: "x".to_string()
+            })
+        );
+        assert_eq!(
+            "1\n3\n1".parse::<AdapterChain>(),
+            Err(ChainError::DuplicateAdapter(1))
+        );
+    }
+}
@@ -1,5 +1,8 @@
+mod simulation;
+
+pub use simulation::{Frames, Neighbourhood, Outcome, Rules, Simulation};
 use std::fmt;
-use std::usize;
+use std::str::FromStr;
 
 static OFFSETS: [(i32, i32); 8] = [
     (-1, -1),
@@ -13,7 +16,7 @@ static OFFSETS: [(i32, i32); 8] = [
 ];
 
 #[derive(Debug, Clone)]
-enum Cell {
+pub enum Cell {
     Floor,
     EmptySeat,
     OccupiedSeat,
@@ -45,10 +48,12 @@ impl fmt::Display for Cell {
 }
 
 #[derive(Debug, Clone)]
-struct Board(Vec<Vec<Cell>>);
+pub struct Board(Vec<Vec<Cell>>);
+
+impl FromStr for Board {
+    type Err = ();
 
-impl Board {
-    fn from_str(input: &str) -> Board {
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut ret = vec![];
 
         for row in input.lines() {
@@ -59,120 +64,7 @@ impl Board {
             ret.push(line);
         }
 
-        Board(ret)
-    }
-
-    fn ray_cast_neighbours(&self, x: usize, y: usize) -> u8 {
-        let mut neighbours_found = 0;
-        for (dy, dx) in OFFSETS.iter() {
-            let mut multiplier = 1;
-            loop {
-                let new_y = (y as i32) + dy * multiplier;
-                let new_x = (x as i32) + dx * multiplier;
-                multiplier += 1;
-                if new_x < 0 || new_y < 0 {
-                    break;
-                }
-                if let Some(row) = self.0.get(new_y as usize) {
-                    match row.get(new_x as usize) {
-                        Some(Cell::Floor) => {
-                            continue;
-                        }
-                        Some(Cell::EmptySeat) => {
-                            break;
-                        }
-                        Some(Cell::OccupiedSeat) => {
-                            neighbours_found += 1;
-                            break;
-                        }
-                        None => {
-                            break;
-                        }
-                    }
-                } else {
-                    break;
-                }
-            }
-        }
-
-        neighbours_found
-    }
-
-    fn cell_neighbors(&self, x: usize, y: usize) -> u8 {
-        let mut ret = 0;
-        for (dy, dx) in OFFSETS.iter() {
-            let new_y = (y as i32) + dy;
-            let new_x = (x as i32) + dx;
-            if new_x < 0 || new_y < 0 {
-                continue;
-            }
-            if let Some(row) = self.0.get(new_y as usize) {
-                if let Some(Cell::OccupiedSeat) = row.get(new_x as usize) {
-                    ret += 1;
-                }
-            }
-            // if let Some(Cell::OccupiedSeat) = self
-            //     .0
-            //     .get(new_y as usize)
-            //     .map(|row| row.get(new_x as usize))
-            //     .flatten()
-            // {
-            //     ret += 1;
-            // }
-        }
-        ret
-    }
-
-    fn next(&self, neighbours_limit: u8, use_ray_cast: bool) -> (Board, bool) {
-        let mut ret = vec![];
-        let mut changes = false;
-        for (y, row) in self.0.iter().enumerate() {
-            let mut new_row: Vec<Cell> = vec![];
-            for (x, cell) in row.iter().enumerate() {
-                let neighbors = if use_ray_cast {
-                    self.ray_cast_neighbours(x, y)
-                } else {
-                    self.cell_neighbors(x, y)
-                };
-
-                match cell {
-                    Cell::Floor => {
-                        new_row.push(Cell::Floor);
-                    }
-                    Cell::EmptySeat => {
-                        if neighbors == 0 {
-                            new_row.push(Cell::OccupiedSeat);
-                            changes = true;
-                        } else {
-                            new_row.push(Cell::EmptySeat);
-                        }
-                    }
-                    Cell::OccupiedSeat => {
-                        if neighbors >= neighbours_limit {
-                            new_row.push(Cell::EmptySeat);
-                            changes = true;
-                        } else {
-                            new_row.push(Cell::OccupiedSeat);
-                        }
-                    }
-                }
-            }
-            ret.push(new_row);
-        }
-
-        (Board(ret), changes)
-    }
-
-    fn count(&self) -> u32 {
-        let mut ret = 0;
-        for row in &self.0 {
-            for cell in row {
-                if let Cell::OccupiedSeat = cell {
-                    ret += 1;
-                }
-            }
-        }
-        ret
+        Ok(Board(ret))
     }
 }
 
@@ -189,48 +81,32 @@ impl fmt::Display for Board {
     }
 }
 
-struct BoardIterator {
-    board: Board,
-    neighbours_limit: usize,
-    use_ray_cast: bool,
-}
-
-impl Iterator for BoardIterator {
-    type Item = Board;
-
-    fn next(&mut self) -> Option<Board> {
-        let (board, is_changed) = self
-            .board
-            .next(self.neighbours_limit as u8, self.use_ray_cast);
-        if !is_changed {
-            None
-        } else {
-            self.board = board.clone();
-            Some(board)
-        }
-    }
-}
-
 pub fn part1(input: &str) -> u32 {
-    let board = Board::from_str(input);
-    let iterator = BoardIterator {
-        board,
-        neighbours_limit: 4,
-        use_ray_cast: false,
-    };
-
-    iterator.last().unwrap().count()
+    let board: Board = input.parse().unwrap();
+    let mut simulation = Simulation::new(
+        &board,
+        Rules {
+            neighbourhood: Neighbourhood::Adjacent,
+            occupy_at_most: 0,
+            vacate_at_least: 4,
+        },
+    );
+    simulation.run(usize::MAX);
+    simulation.occupied() as u32
 }
 
 pub fn part2(input: &str) -> u32 {
-    let board = Board::from_str(input);
-    let iterator = BoardIterator {
-        board,
-        neighbours_limit: 5,
-        use_ray_cast: true,
-    };
-
-    iterator.last().unwrap().count()
+    let board: Board = input.parse().unwrap();
+    let mut simulation = Simulation::new(
+        &board,
+        Rules {
+            neighbourhood: Neighbourhood::LineOfSight,
+            occupy_at_most: 0,
+            vacate_at_least: 5,
+        },
+    );
+    simulation.run(usize::MAX);
+    simulation.occupied() as u32
 }
 
 #[cfg(test)]
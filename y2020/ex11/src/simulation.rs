@@ -0,0 +1,377 @@
+use crate::{Board, Cell, OFFSETS};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Adjacent,
+    LineOfSight,
+    MaxDistance(usize),
+}
+
+impl Neighbourhood {
+    fn max_distance(&self) -> Option<usize> {
+        match self {
+            Neighbourhood::Adjacent => Some(1),
+            Neighbourhood::LineOfSight => None,
+            Neighbourhood::MaxDistance(distance) => Some(*distance),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub neighbourhood: Neighbourhood,
+    pub occupy_at_most: u8,
+    pub vacate_at_least: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Cycle { start: usize, period: usize },
+    Unsettled { generations: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    rules: Rules,
+    layout: Board,
+    seats: Vec<(usize, usize)>,
+    // `neighbours[neighbours_start[i]..neighbours_start[i + 1]]` are the neighbours of seat `i`
+    neighbours_start: Vec<usize>,
+    neighbours: Vec<usize>,
+    current: Vec<bool>,
+    next: Vec<bool>,
+    generation: usize,
+    initial: Vec<bool>,
+    // hashes of the packed states seen so far, checked against the real state on a hit
+    history: HashMap<u64, usize>,
+}
+
+impl Simulation {
+    pub fn new(board: &Board, rules: Rules) -> Self {
+        let mut seat_ids: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut seats = vec![];
+        let mut current = vec![];
+        for (y, row) in board.0.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if !matches!(cell, Cell::Floor) {
+                    seat_ids.insert((y, x), seats.len());
+                    seats.push((y, x));
+                    current.push(matches!(cell, Cell::OccupiedSeat));
+                }
+            }
+        }
+
+        let mut neighbours_start = vec![0];
+        let mut neighbours = vec![];
+        for (y, x) in seats.iter() {
+            for (dy, dx) in OFFSETS.iter() {
+                let mut distance = 1;
+                while rules
+                    .neighbourhood
+                    .max_distance()
+                    .is_none_or(|max| distance <= max)
+                {
+                    let new_y = (*y as i32) + dy * distance as i32;
+                    let new_x = (*x as i32) + dx * distance as i32;
+                    if new_x < 0 || new_y < 0 {
+                        break;
+                    }
+                    match board
+                        .0
+                        .get(new_y as usize)
+                        .and_then(|row| row.get(new_x as usize))
+                    {
+                        Some(Cell::Floor) => distance += 1,
+                        Some(_) => {
+                            neighbours.push(seat_ids[&(new_y as usize, new_x as usize)]);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            neighbours_start.push(neighbours.len());
+        }
+
+        let next = current.clone();
+        let current_copy = current.clone();
+        let mut simulation = Simulation {
+            rules,
+            layout: board.clone(),
+            seats,
+            neighbours_start,
+            neighbours,
+            current,
+            next,
+            generation: 0,
+            initial: current_copy,
+            history: HashMap::new(),
+        };
+        let hash = simulation.state_hash();
+        simulation.history.insert(hash, 0);
+        simulation
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.current.iter().filter(|occupied| **occupied).count()
+    }
+
+    fn advance(&self, current: &[bool], next: &mut [bool]) -> bool {
+        let mut changes = false;
+        for seat in 0..current.len() {
+            let occupied_neighbours = self.neighbours
+                [self.neighbours_start[seat]..self.neighbours_start[seat + 1]]
+                .iter()
+                .filter(|neighbour| current[**neighbour])
+                .count();
+
+            let occupied = current[seat];
+            let state = if occupied {
+                occupied_neighbours < self.rules.vacate_at_least as usize
+            } else {
+                occupied_neighbours <= self.rules.occupy_at_most as usize
+            };
+            changes |= state != occupied;
+            next[seat] = state;
+        }
+        changes
+    }
+
+    pub fn step(&mut self) -> bool {
+        let mut next = std::mem::take(&mut self.next);
+        let changes = self.advance(&self.current, &mut next);
+        self.next = std::mem::replace(&mut self.current, next);
+        self.generation += 1;
+        changes
+    }
+
+    fn state_at(&self, generation: usize) -> Vec<bool> {
+        let mut current = self.initial.clone();
+        let mut next = current.clone();
+        for _ in 0..generation {
+            self.advance(&current, &mut next);
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+    }
+
+    pub fn run(&mut self, max_generations: usize) -> Outcome {
+        for _ in 0..max_generations {
+            if !self.step() {
+                // a fixed point: no need to pack the state to recognise it
+                return Outcome::Cycle {
+                    start: self.generation - 1,
+                    period: 1,
+                };
+            }
+
+            let hash = self.state_hash();
+            match self.history.get(&hash) {
+                Some(start) if self.state_at(*start) == self.current => {
+                    return Outcome::Cycle {
+                        start: *start,
+                        period: self.generation - start,
+                    };
+                }
+                // a collision: the first state keeps the hash
+                Some(_) => {}
+                None => {
+                    self.history.insert(hash, self.generation);
+                }
+            }
+        }
+
+        Outcome::Unsettled {
+            generations: self.generation,
+        }
+    }
+
+    pub fn frame(&self) -> Board {
+        let mut board = self.layout.clone();
+        for ((y, x), occupied) in self.seats.iter().zip(self.current.iter()) {
+            board.0[*y][*x] = if *occupied {
+                Cell::OccupiedSeat
+            } else {
+                Cell::EmptySeat
+            };
+        }
+        board
+    }
+
+    pub fn frames(self) -> Frames {
+        Frames {
+            simulation: self,
+            done: false,
+        }
+    }
+
+    fn state_hash(&self) -> u64 {
+        let mut packed = vec![0u64; self.current.len().div_ceil(64)];
+        for (seat, occupied) in self.current.iter().enumerate() {
+            if *occupied {
+                packed[seat / 64] |= 1 << (seat % 64);
+            }
+        }
+        let mut hasher = DefaultHasher::new();
+        packed.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+pub struct Frames {
+    simulation: Simulation,
+    done: bool,
+}
+
+impl Iterator for Frames {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        if self.done {
+            return None;
+        }
+
+        let frame = self.simulation.frame();
+        self.done = matches!(self.simulation.run(1), Outcome::Cycle { .. });
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+
+    const ADJACENT: Rules = Rules {
+        neighbourhood: Neighbourhood::Adjacent,
+        occupy_at_most: 0,
+        vacate_at_least: 4,
+    };
+
+    #[test]
+    fn test_example_fixed_point() {
+        let mut simulation = Simulation::new(&EXAMPLE.parse().unwrap(), ADJACENT);
+        assert_eq!(
+            simulation.run(100),
+            Outcome::Cycle {
+                start: 5,
+                period: 1
+            }
+        );
+        assert_eq!(simulation.occupied(), 37);
+
+        let rules = Rules {
+            neighbourhood: Neighbourhood::LineOfSight,
+            vacate_at_least: 5,
+            ..ADJACENT
+        };
+        let mut simulation = Simulation::new(&EXAMPLE.parse().unwrap(), rules);
+        assert_eq!(
+            simulation.run(100),
+            Outcome::Cycle {
+                start: 6,
+                period: 1
+            }
+        );
+        assert_eq!(simulation.occupied(), 26);
+    }
+
+    #[test]
+    fn test_frames() {
+        let simulation = Simulation::new(&EXAMPLE.parse().unwrap(), ADJACENT);
+        let frames: Vec<Board> = simulation.frames().collect();
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[0].to_string(), EXAMPLE);
+        assert_eq!(
+            frames[1].to_string(),
+            EXAMPLE.replace('L', "#"),
+            "Every seat gets occupied in the first round"
+        );
+    }
+
+    #[test]
+    fn test_oscillation() {
+        // two facing seats flip together between empty and occupied forever
+        let rules = Rules {
+            neighbourhood: Neighbourhood::Adjacent,
+            occupy_at_most: 0,
+            vacate_at_least: 1,
+        };
+        let mut simulation = Simulation::new(&"LL".parse().unwrap(), rules);
+        assert_eq!(
+            simulation.run(100),
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            }
+        );
+
+        let frames: Vec<String> = Simulation::new(&"LL".parse().unwrap(), rules)
+            .frames()
+            .map(|frame| frame.to_string())
+            .collect();
+        assert_eq!(frames, ["LL", "##"]);
+    }
+
+    #[test]
+    fn test_state_at() {
+        let mut simulation = Simulation::new(&EXAMPLE.parse().unwrap(), ADJACENT);
+        simulation.run(3);
+        assert_eq!(simulation.generation(), 3);
+        assert_eq!(simulation.state_at(3), simulation.current);
+        assert_ne!(simulation.state_at(2), simulation.current);
+        // only a hash per generation is kept
+        assert_eq!(simulation.history.len(), 4);
+    }
+
+    #[test]
+    fn test_unsettled() {
+        let rules = Rules {
+            neighbourhood: Neighbourhood::Adjacent,
+            occupy_at_most: 0,
+            vacate_at_least: 1,
+        };
+        let mut simulation = Simulation::new(&"LL".parse().unwrap(), rules);
+        assert_eq!(simulation.run(1), Outcome::Unsettled { generations: 1 });
+    }
+
+    #[test]
+    fn test_max_distance() {
+        let board: Board = "L..L...L".parse().unwrap();
+        let simulation = Simulation::new(&board, ADJACENT);
+        assert_eq!(simulation.neighbours.len(), 0);
+
+        let rules = Rules {
+            neighbourhood: Neighbourhood::MaxDistance(3),
+            ..ADJACENT
+        };
+        let simulation = Simulation::new(&board, rules);
+        // the first two seats see each other, the third one is too far away
+        assert_eq!(simulation.neighbours, [1, 0]);
+
+        let rules = Rules {
+            neighbourhood: Neighbourhood::LineOfSight,
+            ..ADJACENT
+        };
+        let simulation = Simulation::new(&board, rules);
+        assert_eq!(simulation.neighbours, [1, 0, 2, 1]);
+    }
+}
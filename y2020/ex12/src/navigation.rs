@@ -0,0 +1,345 @@
+use std::{fmt::Write, ops::Add, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum InstructionError {
+    #[error("Empty instruction")]
+    Empty,
+    #[error("Invalid action '{0}'")]
+    InvalidAction(char),
+    #[error("Invalid value '{0}'")]
+    InvalidValue(String),
+    #[error("Can only turn by multiples of 90 degrees, found {0}")]
+    InvalidAngle(i32),
+    #[error("Invalid mode '{0}', expected '@ship' or '@waypoint'")]
+    InvalidMode(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Invalid instruction on line {line}: {source}")]
+pub struct ProgramError {
+    line: usize,
+    source: InstructionError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    North,
+    South,
+    East,
+    West,
+    Right,
+    Left,
+    Forward,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Ship,
+    Waypoint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Go(Action, i32),
+    SetMode(Mode),
+}
+
+impl FromStr for Instruction {
+    type Err = InstructionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(mode) = s.strip_prefix('@') {
+            return match mode {
+                "ship" => Ok(Instruction::SetMode(Mode::Ship)),
+                "waypoint" => Ok(Instruction::SetMode(Mode::Waypoint)),
+                _ => Err(InstructionError::InvalidMode(s.to_string())),
+            };
+        }
+
+        let mut chars = s.chars();
+        let action = match chars.next().ok_or(InstructionError::Empty)? {
+            'N' => Action::North,
+            'S' => Action::South,
+            'E' => Action::East,
+            'W' => Action::West,
+            'R' => Action::Right,
+            'L' => Action::Left,
+            'F' => Action::Forward,
+            c => return Err(InstructionError::InvalidAction(c)),
+        };
+        let value: i32 = chars
+            .as_str()
+            .parse()
+            .map_err(|_| InstructionError::InvalidValue(chars.as_str().to_string()))?;
+        if matches!(action, Action::Right | Action::Left) && value % 90 != 0 {
+            return Err(InstructionError::InvalidAngle(value));
+        }
+
+        Ok(Instruction::Go(action, value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    fn scale(self, k: i64) -> Self {
+        Point::new(self.x * k, self.y * k)
+    }
+
+    fn rotate(self, quarter_turns: i32) -> Self {
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => Point::new(self.y, -self.x),
+            2 => Point::new(-self.x, -self.y),
+            _ => Point::new(-self.y, self.x),
+        }
+    }
+
+    pub fn manhattan_distance(&self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Navigator {
+    mode: Mode,
+    position: Point,
+    heading: Point,
+    waypoint: Point,
+    track: Vec<Point>,
+}
+
+impl Navigator {
+    pub fn new(mode: Mode) -> Self {
+        Navigator {
+            mode,
+            position: Point::default(),
+            heading: Point::new(1, 0),
+            waypoint: Point::new(10, 1),
+            track: vec![Point::default()],
+        }
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    pub fn waypoint(&self) -> Point {
+        self.waypoint
+    }
+
+    pub fn track(&self) -> &[Point] {
+        &self.track
+    }
+
+    pub fn execute(&mut self, instruction: Instruction) {
+        let (action, value) = match instruction {
+            Instruction::SetMode(mode) => {
+                self.mode = mode;
+                return;
+            }
+            Instruction::Go(action, value) => (action, value),
+        };
+
+        let direction = match action {
+            Action::North => Point::new(0, 1),
+            Action::South => Point::new(0, -1),
+            Action::East => Point::new(1, 0),
+            Action::West => Point::new(-1, 0),
+            Action::Right | Action::Left => {
+                let quarter_turns = if action == Action::Right {
+                    value / 90
+                } else {
+                    -(value / 90)
+                };
+                match self.mode {
+                    Mode::Ship => self.heading = self.heading.rotate(quarter_turns),
+                    Mode::Waypoint => self.waypoint = self.waypoint.rotate(quarter_turns),
+                }
+                return;
+            }
+            Action::Forward => {
+                let direction = match self.mode {
+                    Mode::Ship => self.heading,
+                    Mode::Waypoint => self.waypoint,
+                };
+                self.move_ship(direction.scale(value as i64));
+                return;
+            }
+        };
+
+        let shift = direction.scale(value as i64);
+        match self.mode {
+            Mode::Ship => self.move_ship(shift),
+            Mode::Waypoint => self.waypoint = self.waypoint + shift,
+        }
+    }
+
+    fn move_ship(&mut self, shift: Point) {
+        self.position = self.position + shift;
+        self.track.push(self.position);
+    }
+
+    pub fn run(&mut self, program: &str) -> Result<(), ProgramError> {
+        for (i, line) in program.lines().enumerate() {
+            let instruction = line.parse().map_err(|source| ProgramError {
+                line: i + 1,
+                source,
+            })?;
+            self.execute(instruction);
+        }
+        Ok(())
+    }
+
+    pub fn to_svg(&self) -> String {
+        let min_x = self.track.iter().map(|p| p.x).min().unwrap();
+        let max_x = self.track.iter().map(|p| p.x).max().unwrap();
+        let min_y = self.track.iter().map(|p| -p.y).min().unwrap();
+        let max_y = self.track.iter().map(|p| -p.y).max().unwrap();
+        let margin = 1 + (max_x - min_x).max(max_y - min_y) / 20;
+
+        let mut points = String::new();
+        for (i, point) in self.track.iter().enumerate() {
+            if i > 0 {
+                points.push(' ');
+            }
+            write!(points, "{},{}", point.x, -point.y).unwrap();
+        }
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n  \
+             <polyline points=\"{}\" fill=\"none\" stroke=\"black\" \
+             vector-effect=\"non-scaling-stroke\"/>\n</svg>\n",
+            min_x - margin,
+            min_y - margin,
+            max_x - min_x + 2 * margin,
+            max_y - min_y + 2 * margin,
+            points
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "F10\nN3\nF7\nR90\nF11";
+
+    #[test]
+    fn test_ship_mode() {
+        let mut navigator = Navigator::new(Mode::Ship);
+        navigator.run(EXAMPLE).unwrap();
+        assert_eq!(navigator.position(), Point::new(17, -8));
+        assert_eq!(
+            navigator.track(),
+            [
+                Point::new(0, 0),
+                Point::new(10, 0),
+                Point::new(10, 3),
+                Point::new(17, 3),
+                Point::new(17, -8)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_waypoint_mode() {
+        let mut navigator = Navigator::new(Mode::Waypoint);
+        navigator.run(EXAMPLE).unwrap();
+        assert_eq!(navigator.position(), Point::new(214, -72));
+        assert_eq!(navigator.waypoint(), Point::new(4, -10));
+        assert_eq!(navigator.position().manhattan_distance(), 286);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let p = Point::new(10, 4);
+        for (instruction, expected) in [
+            ("R90", Point::new(4, -10)),
+            ("L270", Point::new(4, -10)),
+            ("R-270", Point::new(4, -10)),
+            ("R450", Point::new(4, -10)),
+            ("L90", Point::new(-4, 10)),
+            ("R-90", Point::new(-4, 10)),
+            ("L180", Point::new(-10, -4)),
+            ("R720", Point::new(10, 4)),
+            ("L0", Point::new(10, 4)),
+        ] {
+            let mut navigator = Navigator::new(Mode::Waypoint);
+            navigator.waypoint = p;
+            navigator.execute(instruction.parse().unwrap());
+            assert_eq!(navigator.waypoint(), expected, "{}", instruction);
+        }
+
+        assert_eq!(
+            "R45".parse::<Instruction>(),
+            Err(InstructionError::InvalidAngle(45))
+        );
+        assert_eq!(
+            "L-100".parse::<Instruction>(),
+            Err(InstructionError::InvalidAngle(-100))
+        );
+    }
+
+    #[test]
+    fn test_mixed_modes() {
+        let mut navigator = Navigator::new(Mode::Ship);
+        navigator
+            .run("F2\n@waypoint\nN1\nF1\nR90\n@ship\nL90\nF3\n@waypoint\nF1")
+            .unwrap();
+        // 2 east, then to the waypoint (10, 2) once, 3 north, and to the rotated
+        // waypoint (2, -10) once
+        assert_eq!(navigator.position(), Point::new(14, -5));
+        assert_eq!(navigator.track().len(), 5);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut navigator = Navigator::new(Mode::Ship);
+        let err = navigator.run("F10\nR91").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid instruction on line 2: Can only turn by multiples of 90 degrees, found 91"
+        );
+        assert_eq!(
+            "X1".parse::<Instruction>(),
+            Err(InstructionError::InvalidAction('X'))
+        );
+        assert_eq!(
+            "Fa".parse::<Instruction>(),
+            Err(InstructionError::InvalidValue("a".to_string()))
+        );
+        assert_eq!("".parse::<Instruction>(), Err(InstructionError::Empty));
+        assert!("@plane".parse::<Instruction>().is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut navigator = Navigator::new(Mode::Ship);
+        navigator.run("F10\nN3").unwrap();
+        assert_eq!(
+            navigator.to_svg(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -4 12 5\">
+  <polyline points=\"0,0 10,0 10,-3\" fill=\"none\" stroke=\"black\" vector-effect=\"non-scaling-stroke\"/>
+</svg>
+"
+        );
+    }
+}
@@ -1,9 +1,12 @@
+mod memory;
+
+pub use memory::{AddressSet, FloatingMemory};
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq)]
-enum Instr<'a> {
+pub enum Instr<'a> {
     Mask(&'a str),
     Mem(u64, u64),
 }
@@ -12,55 +15,79 @@ impl<'a> TryFrom<&'a str> for Instr<'a> {
     type Error = String;
 
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
-        if s.starts_with("mask") {
-            Ok(Instr::Mask(&s[7..]))
-        } else if s.starts_with("mem") {
-            let addr_value = &mut s[4..].split("] = ");
-            let addr: u64 = addr_value.next().unwrap().parse().unwrap();
-            let value: u64 = addr_value.next().unwrap().parse().unwrap();
+        let invalid = || format!("Invalid line found: {s}");
+        if let Some(mask) = s.strip_prefix("mask = ") {
+            Ok(Instr::Mask(mask))
+        } else if let Some(mem) = s.strip_prefix("mem[") {
+            let (addr, value) = mem.split_once("] = ").ok_or_else(invalid)?;
+            let addr: u64 = addr.parse().map_err(|_| invalid())?;
+            let value: u64 = value.parse().map_err(|_| invalid())?;
             Ok(Instr::Mem(addr, value))
         } else {
-            Err(format!("Invalid line found: {s}"))
+            Err(invalid())
         }
     }
 }
 
-struct MaskParts {
+#[derive(Debug, PartialEq)]
+pub struct MaskParts {
     or_mask: u64,
     and_mask: u64,
-    floating_bits: Vec<usize>,
+    floating: u64,
 }
 
 impl FromStr for MaskParts {
-    type Err = ();
+    type Err = String;
 
     fn from_str(mask: &str) -> Result<Self, Self::Err> {
+        if mask.len() > 64 {
+            return Err(format!("Mask longer than 64 bits: {mask}"));
+        }
+
         let mut and_mask = 0;
         let mut or_mask = 0;
-        let mut floating_bits: Vec<usize> = Vec::with_capacity(36);
+        let mut floating = 0;
 
-        mask.chars().enumerate().for_each(|(i, c)| {
+        for c in mask.chars() {
             and_mask <<= 1;
             or_mask <<= 1;
-            if c == '1' {
-                or_mask |= 1;
-            }
-            if c != '0' {
-                and_mask |= 1;
-            }
-            if c == 'X' {
-                floating_bits.push(35 - i)
+            floating <<= 1;
+            match c {
+                '0' => {}
+                '1' => {
+                    or_mask |= 1;
+                    and_mask |= 1;
+                }
+                'X' => {
+                    and_mask |= 1;
+                    floating |= 1;
+                }
+                _ => return Err(format!("Invalid character '{c}' in mask: {mask}")),
             }
-        });
+        }
 
         Ok(MaskParts {
             or_mask,
             and_mask,
-            floating_bits,
+            floating,
         })
     }
 }
 
+impl MaskParts {
+    pub fn apply_to_value(&self, value: u64) -> u64 {
+        value & self.and_mask | self.or_mask
+    }
+
+    pub fn apply_to_address(&self, address: u64) -> AddressSet {
+        AddressSet::new(address | self.or_mask, self.floating)
+    }
+
+    pub fn addresses_per_write(&self) -> u128 {
+        1 << self.floating.count_ones()
+    }
+}
+
 pub fn part1(input: &str) -> u64 {
     let instr = input.lines().map(|x| x.try_into().unwrap());
 
@@ -73,7 +100,7 @@ pub fn part1(input: &str) -> u64 {
                 mask_parts = mask.parse().unwrap();
             }
             Instr::Mem(key, val) => {
-                mem.insert(key, val & mask_parts.and_mask | mask_parts.or_mask);
+                mem.insert(key, mask_parts.apply_to_value(val));
             }
         }
     }
@@ -81,37 +108,26 @@ pub fn part1(input: &str) -> u64 {
     mem.values().sum()
 }
 
-pub fn part2(input: &str) -> u64 {
-    let instr = input.lines().map(|x| x.try_into().unwrap());
+pub fn decode_v2(input: &str) -> Result<FloatingMemory, String> {
+    let mut mem = FloatingMemory::new();
+    let mut mask_parts: MaskParts = "0".repeat(36).parse()?;
 
-    let mut mem: HashMap<u64, u64> = HashMap::new();
-    let mut mask_parts: MaskParts = "X".repeat(36).parse().unwrap();
-
-    for instruction in instr {
-        match instruction {
+    for line in input.lines() {
+        match line.try_into()? {
             Instr::Mask(mask) => {
-                mask_parts = mask.parse().unwrap();
+                mask_parts = mask.parse()?;
             }
             Instr::Mem(addr, val) => {
-                let base_addr = addr | mask_parts.or_mask;
-                // Now we have to do n inserts where n = 2.pow(numX)
-                for i in 0..2_u64.pow(mask_parts.floating_bits.len() as u32) {
-                    let mut final_addr = base_addr;
-                    for (from_bit, to_bit) in mask_parts.floating_bits.iter().enumerate() {
-                        if i & (1 << from_bit) != 0 {
-                            final_addr |= 1 << to_bit;
-                        } else {
-                            final_addr &= !(1 << to_bit);
-                        }
-                    }
-
-                    mem.insert(final_addr, val);
-                }
+                mem.write(mask_parts.apply_to_address(addr), val);
             }
         }
     }
 
-    mem.values().sum()
+    Ok(mem)
+}
+
+pub fn part2(input: &str) -> u64 {
+    u64::try_from(decode_v2(input).unwrap().sum()).expect("The sum of the memory overflows a u64")
 }
 
 #[cfg(test)]
@@ -129,6 +145,54 @@ mod tests {
         assert_eq!(mem, Instr::Mem(42, 100));
     }
 
+    #[test]
+    fn test_mask_parts() {
+        let mask: MaskParts = "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".parse().unwrap();
+        assert_eq!(mask.apply_to_value(11), 73);
+        assert_eq!(mask.apply_to_value(101), 101);
+        assert_eq!(mask.apply_to_value(0), 64);
+
+        let mask: MaskParts = "000000000000000000000000000000X1001X".parse().unwrap();
+        assert_eq!(mask.addresses_per_write(), 4);
+        let addresses = mask.apply_to_address(42);
+        assert!([26, 27, 58, 59].iter().all(|a| addresses.contains(*a)));
+
+        // 64 bit addresses
+        let mask: MaskParts = format!("1{}", "X".repeat(63)).parse().unwrap();
+        assert_eq!(mask.addresses_per_write(), 1 << 63);
+        assert!(mask.apply_to_address(7).contains(u64::MAX));
+        assert!(!mask.apply_to_address(7).contains(7));
+
+        assert!("X".repeat(65).parse::<MaskParts>().is_err());
+        assert!("X2".parse::<MaskParts>().is_err());
+    }
+
+    #[test]
+    fn test_decode_v2() {
+        let input = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1";
+        let memory = decode_v2(input).unwrap();
+        assert_eq!(memory.sum(), 208);
+        assert_eq!(memory.addresses_touched(), 10);
+
+        // way too many addresses to write one by one
+        let input = format!(
+            "mask = {}1X\nmem[0] = 3\nmask = {}X\nmem[0] = 5",
+            "X".repeat(62),
+            "0".repeat(63)
+        );
+        let memory = decode_v2(&input).unwrap();
+        assert_eq!(memory.addresses_touched(), (1 << 63) + 2);
+        assert_eq!(memory.sum(), 3 * (1 << 63) + 5 * 2);
+
+        assert_eq!(
+            decode_v2("mem[a] = 1").unwrap_err(),
+            "Invalid line found: mem[a] = 1"
+        );
+    }
+
     #[test]
     fn part_1() {
         let input = include_str!("../input.txt");
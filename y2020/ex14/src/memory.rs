@@ -0,0 +1,158 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressSet {
+    fixed: u64,
+    floating: u64,
+}
+
+impl AddressSet {
+    pub fn new(address: u64, floating: u64) -> Self {
+        AddressSet {
+            fixed: address & !floating,
+            floating,
+        }
+    }
+
+    // up to 2^64 addresses, hence the u128
+    pub fn count(&self) -> u128 {
+        1 << self.floating.count_ones()
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address & !self.floating == self.fixed
+    }
+
+    pub fn intersection(&self, other: &AddressSet) -> Option<AddressSet> {
+        let fixed_in_both = !self.floating & !other.floating;
+        if (self.fixed ^ other.fixed) & fixed_in_both != 0 {
+            return None;
+        }
+        Some(AddressSet {
+            fixed: self.fixed | other.fixed,
+            floating: self.floating & other.floating,
+        })
+    }
+
+    pub fn difference(&self, other: &AddressSet) -> Vec<AddressSet> {
+        let common = match self.intersection(other) {
+            None => return vec![*self],
+            Some(common) => common,
+        };
+
+        // for every bit that floats here but is fixed in the common part, split off the
+        // addresses with the other value of that bit, then pin it to the common value
+        let mut pieces = vec![];
+        let mut rest = *self;
+        let mut bits = self.floating & !common.floating;
+        while bits != 0 {
+            let bit = bits & bits.wrapping_neg();
+            bits &= !bit;
+            rest.floating &= !bit;
+            pieces.push(AddressSet {
+                fixed: rest.fixed | (!common.fixed & bit),
+                floating: rest.floating,
+            });
+            rest.fixed |= common.fixed & bit;
+        }
+        pieces
+    }
+}
+
+// writes are kept disjoint: a new one is cut out of the older ones it overlaps
+#[derive(Debug, Clone, Default)]
+pub struct FloatingMemory {
+    writes: Vec<(AddressSet, u64)>,
+}
+
+impl FloatingMemory {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn write(&mut self, addresses: AddressSet, value: u64) {
+        let mut writes = Vec::with_capacity(self.writes.len() + 1);
+        for (set, old_value) in self.writes.drain(..) {
+            writes.extend(
+                set.difference(&addresses)
+                    .into_iter()
+                    .map(|piece| (piece, old_value)),
+            );
+        }
+        writes.push((addresses, value));
+        self.writes = writes;
+    }
+
+    pub fn read(&self, address: u64) -> u64 {
+        self.writes
+            .iter()
+            .find(|(set, _)| set.contains(address))
+            .map_or(0, |(_, value)| *value)
+    }
+
+    pub fn addresses_touched(&self) -> u128 {
+        self.writes.iter().map(|(set, _)| set.count()).sum()
+    }
+
+    pub fn sum(&self) -> u128 {
+        self.writes
+            .iter()
+            .map(|(set, value)| set.count() * *value as u128)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(set: &AddressSet) -> Vec<u64> {
+        (0..64).filter(|a| set.contains(*a)).collect()
+    }
+
+    #[test]
+    fn test_address_set() {
+        // 0b1X0X
+        let a = AddressSet::new(0b1000, 0b0101);
+        assert_eq!(a.count(), 4);
+        assert_eq!(addresses(&a), [8, 9, 12, 13]);
+
+        // 0bX10X
+        let b = AddressSet::new(0b0100, 0b1001);
+        assert_eq!(a.intersection(&b), Some(AddressSet::new(0b1100, 0b0001)));
+        assert_eq!(a.intersection(&AddressSet::new(0b0000, 0b0101)), None);
+
+        let difference = a.difference(&b);
+        let mut left: Vec<u64> = difference.iter().flat_map(addresses).collect();
+        left.sort_unstable();
+        assert_eq!(left, [8, 9]);
+        assert_eq!(difference.iter().map(AddressSet::count).sum::<u128>(), 2);
+
+        let everything = AddressSet::new(0, u64::MAX);
+        assert_eq!(everything.count(), 1 << 64);
+        assert!(a.difference(&everything).is_empty());
+        assert_eq!(
+            everything
+                .difference(&a)
+                .iter()
+                .map(AddressSet::count)
+                .sum::<u128>(),
+            (1 << 64) - 4
+        );
+    }
+
+    #[test]
+    fn test_memory() {
+        let mut memory = FloatingMemory::new();
+        memory.write(AddressSet::new(0b11010, 0b100001), 100);
+        memory.write(AddressSet::new(0b10000, 0b1011), 1);
+        assert_eq!(memory.addresses_touched(), 4 + 8 - 2);
+        assert_eq!(memory.sum(), 208);
+        assert_eq!(memory.read(0b11010), 1);
+        assert_eq!(memory.read(0b111010), 100);
+        assert_eq!(memory.read(0b111), 0);
+
+        // 60 floating bits, in a single write
+        memory.write(AddressSet::new(0, (1 << 60) - 1), 2);
+        assert_eq!(memory.addresses_touched(), 1 << 60);
+        assert_eq!(memory.sum(), 2 << 60);
+    }
+}
@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ex15::{parse_start, VanEck};
+
+// takes minutes and 4 GiB, so it only runs with `cargo bench --features billion`
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = include_str!("../input.txt");
+
+    // every number said is below the turn, so a dense table as long as the game never
+    // needs the sparse map
+    let mut group = c.benchmark_group("ex15::billion");
+    group.sample_size(10);
+    group.bench_function("1e9 turns", |b| {
+        b.iter(|| {
+            VanEck::new(parse_start(black_box(input)))
+                .with_dense_limit(1_000_000_000)
+                .with_memory_limit(4 << 30)
+                .spoken_at(1_000_000_000)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,37 +1,27 @@
-fn game<const N: usize>(input: &str) -> usize {
-    let start_vals: Vec<usize> = input
-        .split(',')
-        .map(|x| x.parse().expect("Invalid input"))
-        .collect();
+mod van_eck;
 
-    let mut occurrence = vec![0_usize; N].into_boxed_slice();
+pub use van_eck::{VanEck, VanEckError, DEFAULT_DENSE_LIMIT};
 
-    for (turn, n) in start_vals.iter().take(start_vals.len() - 1).enumerate() {
-        occurrence[*n] = turn + 1;
-    }
-
-    let mut next = *start_vals.last().unwrap();
-    let mut new_next;
-    for turn in start_vals.len()..N {
-        let last_seen = occurrence.get(next).unwrap();
-        if *last_seen != 0 {
-            new_next = turn - last_seen;
-        } else {
-            new_next = 0;
-        }
-        occurrence[next] = turn;
-        next = new_next;
-    }
+pub fn parse_start(input: &str) -> Vec<u64> {
+    input
+        .trim()
+        .split(',')
+        .map(|x| x.parse().expect("Invalid input"))
+        .collect()
+}
 
-    next
+pub fn spoken_at(input: &str, turn: usize) -> u64 {
+    VanEck::new(parse_start(input))
+        .spoken_at(turn as u64)
+        .unwrap()
 }
 
-pub fn part1(input: &str) -> usize {
-    game::<2020>(input)
+pub fn part1(input: &str) -> u64 {
+    spoken_at(input, 2020)
 }
 
-pub fn part2(input: &str) -> usize {
-    game::<30_000_000>(input)
+pub fn part2(input: &str) -> u64 {
+    spoken_at(input, 30_000_000)
 }
 
 #[cfg(test)]
@@ -60,11 +50,11 @@ mod tests {
         ];
 
         for (input, output) in expectations_2020.iter() {
-            assert_eq!(game::<2020>(input), *output);
+            assert_eq!(spoken_at(input, 2020), *output);
         }
 
         for (input, output) in expectations_30_000_000.iter() {
-            assert_eq!(game::<30_000_000>(input), *output);
+            assert_eq!(spoken_at(input, 30_000_000), *output);
         }
     }
 
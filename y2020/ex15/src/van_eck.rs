@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use thiserror::Error;
+
+pub const DEFAULT_DENSE_LIMIT: usize = 1 << 24;
+
+const SPARSE_ENTRY: usize = std::mem::size_of::<(u64, u32)>() + 1;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VanEckError {
+    #[error("Turn {turn} needs more than the memory limit of {limit} bytes")]
+    MemoryLimitExceeded { turn: u32, limit: usize },
+    #[error("There is no turn {0}")]
+    InvalidTurn(u64),
+}
+
+#[derive(Debug, Clone)]
+pub struct VanEck {
+    start: Vec<u64>,
+    dense: Vec<u32>,
+    dense_limit: usize,
+    sparse: HashMap<u64, u32>,
+    memory_limit: Option<usize>,
+    error: Option<VanEckError>,
+    turn: u32,
+    last: u64,
+}
+
+impl VanEck {
+    pub fn new(start: Vec<u64>) -> Self {
+        VanEck {
+            start,
+            dense: vec![],
+            dense_limit: DEFAULT_DENSE_LIMIT,
+            sparse: HashMap::new(),
+            memory_limit: None,
+            error: None,
+            turn: 0,
+            last: 0,
+        }
+    }
+
+    pub fn with_dense_limit(self, limit: usize) -> Self {
+        VanEck {
+            dense_limit: limit,
+            ..self
+        }
+    }
+
+    pub fn with_memory_limit(self, limit: usize) -> Self {
+        VanEck {
+            memory_limit: Some(limit),
+            ..self
+        }
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn error(&self) -> Option<&VanEckError> {
+        self.error.as_ref()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        Self::memory(self.dense.capacity(), self.sparse.capacity())
+    }
+
+    fn memory(dense: usize, sparse: usize) -> usize {
+        dense * std::mem::size_of::<u32>() + sparse * SPARSE_ENTRY
+    }
+
+    fn check_memory(&self, dense: usize, sparse: usize) -> Result<(), VanEckError> {
+        match self.memory_limit {
+            Some(limit) if Self::memory(dense, sparse) > limit => {
+                Err(VanEckError::MemoryLimitExceeded {
+                    turn: self.turn + 1,
+                    limit,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn say(&mut self, number: u64, turn: u32) -> Result<u32, VanEckError> {
+        if number < self.dense_limit as u64 {
+            let index = number as usize;
+            if index >= self.dense.len() {
+                let len = (index + 1).next_power_of_two().min(self.dense_limit);
+                self.check_memory(len, self.sparse.capacity())?;
+                self.dense.reserve_exact(len - self.dense.len());
+                self.dense.resize(len, 0);
+            }
+            Ok(std::mem::replace(&mut self.dense[index], turn))
+        } else {
+            // a full map doubles when a new number comes in
+            let capacity = self.sparse.capacity();
+            if self.sparse.len() == capacity && !self.sparse.contains_key(&number) {
+                self.check_memory(self.dense.capacity(), (capacity * 2).max(4))?;
+            }
+            Ok(self.sparse.insert(number, turn).unwrap_or(0))
+        }
+    }
+
+    pub fn spoken_at(mut self, turn: u64) -> Result<u64, VanEckError> {
+        if turn == 0 || turn > u32::MAX as u64 || self.start.is_empty() {
+            return Err(VanEckError::InvalidTurn(turn));
+        }
+        match self.nth(turn as usize - 1) {
+            Some(number) => Ok(number),
+            None => Err(self.error.unwrap_or(VanEckError::InvalidTurn(turn))),
+        }
+    }
+}
+
+impl Iterator for VanEck {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.turn == u32::MAX || self.start.is_empty() || self.error.is_some() {
+            return None;
+        }
+
+        let turn = self.turn + 1;
+        let next = if turn == 1 {
+            self.start[0]
+        } else {
+            let previous_turn = self.turn;
+            let seen = match self.say(self.last, previous_turn) {
+                Ok(seen) => seen,
+                Err(err) => {
+                    self.error = Some(err);
+                    return None;
+                }
+            };
+            match self.start.get(turn as usize - 1) {
+                Some(n) => *n,
+                None if seen == 0 => 0,
+                None => (previous_turn - seen) as u64,
+            }
+        };
+
+        self.turn = turn;
+        self.last = next;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence() {
+        let spoken: Vec<u64> = VanEck::new(vec![0, 3, 6]).take(10).collect();
+        assert_eq!(spoken, [0, 3, 6, 0, 3, 3, 1, 0, 4, 0]);
+
+        assert_eq!(VanEck::new(vec![]).next(), None);
+        let spoken: Vec<u64> = VanEck::new(vec![7]).take(5).collect();
+        assert_eq!(spoken, [7, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_long_start() {
+        // repeated and huge numbers in a long starting sequence
+        let start = vec![5, 1_000_000_000_000, 5, 2, 1_000_000_000_000, 1, 0, 5];
+        let mut game = VanEck::new(start.clone());
+        let spoken: Vec<u64> = game.by_ref().take(10).collect();
+        assert_eq!(spoken[..8], start[..]);
+        // 5 was said in turns 3 and 8, then again in turn 9
+        assert_eq!(spoken[8..], [5, 1]);
+        assert_eq!(game.turn(), 10);
+    }
+
+    #[test]
+    fn test_hybrid_storage() {
+        let dense: Vec<u64> = VanEck::new(vec![1, 20, 8, 6, 3]).take(100_000).collect();
+        let mut game = VanEck::new(vec![1, 20, 8, 6, 3]).with_dense_limit(64);
+        let hybrid: Vec<u64> = game.by_ref().take(100_000).collect();
+        assert_eq!(dense, hybrid);
+        assert!(game.dense.len() <= 64);
+        assert!(!game.sparse.is_empty());
+        assert!(game.memory_usage() > 0);
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let start = vec![1, 20, 8, 6, 3];
+        let game = VanEck::new(start.clone()).with_memory_limit(1 << 12);
+        assert_eq!(
+            game.spoken_at(100_000),
+            Err(VanEckError::MemoryLimitExceeded {
+                turn: 1_174,
+                limit: 1 << 12
+            })
+        );
+
+        // the sparse map counts too
+        let mut game = VanEck::new(start.clone())
+            .with_dense_limit(64)
+            .with_memory_limit(1 << 12);
+        assert!(game.by_ref().take(100_000).count() < 100_000);
+        assert!(game.memory_usage() <= 1 << 12);
+        assert!(matches!(
+            game.error(),
+            Some(VanEckError::MemoryLimitExceeded { .. })
+        ));
+
+        let game = VanEck::new(start).with_memory_limit(1 << 20);
+        assert_eq!(game.spoken_at(2020), Ok(6));
+        assert_eq!(
+            VanEck::new(vec![]).spoken_at(1),
+            Err(VanEckError::InvalidTurn(1))
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use ex16::part1;
+use ex16::{part1, part2};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let input = include_str!("../input.txt");
     c.bench_function("ex16::part1", |b| b.iter(|| part1(black_box(input))));
+    c.bench_function("ex16::part2", |b| b.iter(|| part2(black_box(input))));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,149 +1,223 @@
-#[macro_use]
-extern crate lazy_static;
-use regex::Regex;
-use std::{collections::HashSet, ops::RangeInclusive, str::FromStr};
-
-lazy_static! {
-    static ref RULE_REGEX: Regex =
-        Regex::new(r"^(.+): ([0-9]+)-([0-9]+) or ([0-9]+)-([0-9]+)$").unwrap();
+mod matching;
+
+use csp::{Solver, Var};
+pub use matching::BipartiteGraph;
+use std::{ops::RangeInclusive, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("Invalid rule '{0}'")]
+    InvalidRule(String),
+    #[error("Invalid ticket '{0}'")]
+    InvalidTicket(String),
+    #[error("Missing section '{0}'")]
+    MissingSection(&'static str),
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
-struct Rule {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
     name: String,
-    ranges: (RangeInclusive<u64>, RangeInclusive<u64>),
+    ranges: Vec<RangeInclusive<u64>>,
 }
 
 impl Rule {
-    fn contains(&self, n: &u64) -> bool {
-        self.ranges.0.contains(n) || self.ranges.1.contains(n)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn contains(&self, n: &u64) -> bool {
+        self.ranges.iter().any(|range| range.contains(n))
     }
 }
 
 impl FromStr for Rule {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let capture = RULE_REGEX.captures(line).unwrap();
-
-        let name = String::from(&capture[1]);
-        let range_1_start: u64 = capture[2].parse().unwrap();
-        let range_1_end: u64 = capture[3].parse().unwrap();
-        let range_2_start: u64 = capture[4].parse().unwrap();
-        let range_2_end: u64 = capture[5].parse().unwrap();
+        let invalid = || ParseError::InvalidRule(line.to_string());
+        let (name, ranges) = line.split_once(": ").ok_or_else(invalid)?;
+        let ranges = ranges
+            .split(" or ")
+            .map(|range| {
+                let (start, end) = range.split_once('-')?;
+                Some(start.parse().ok()?..=end.parse().ok()?)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
 
         Ok(Rule {
-            name,
-            ranges: (
-                RangeInclusive::new(range_1_start, range_1_end),
-                RangeInclusive::new(range_2_start, range_2_end),
-            ),
+            name: name.to_string(),
+            ranges,
         })
     }
 }
 
-pub fn part1(input: &str) -> u64 {
-    let mut i = input.split("\n\n");
-    let unparsed_rules = i.next().unwrap();
-    let _own_ticket = i.next().unwrap();
-    let other_tickets = i.next().unwrap();
-
-    let rules: Vec<Rule> = unparsed_rules.lines().map(|l| l.parse().unwrap()).collect();
-
-    other_tickets
-        .lines()
-        .skip(1)
-        // We don't car about which tickets are invalid
-        // So we can flat into a single iterator
-        .flat_map(|l| l.split(','))
-        // Cast to u64
-        .map(&str::parse::<u64>)
-        // Removing the non-number
-        .filter_map(Result::ok)
-        // or
-        // .map(Result::unwrap)
-        .filter(|n| !rules.iter().any(|rule| rule.contains(n)))
-        // Sum together
-        .sum()
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    #[error("Only {matched} of the {columns} columns can be assigned to a distinct field")]
+    NoAssignment { matched: usize, columns: usize },
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut i = input.split("\n\n");
-    let unparsed_rules = i.next().unwrap();
-    let own_ticket = i.next().unwrap();
-    let other_tickets = i.next().unwrap();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<'a> {
+    Unique(Vec<&'a str>),
+    Ambiguous(Vec<Vec<&'a str>>),
+}
 
-    let rules: Vec<Rule> = unparsed_rules.lines().map(|l| l.parse().unwrap()).collect();
+impl<'a> Resolution<'a> {
+    pub fn unique(self) -> Option<Vec<&'a str>> {
+        match self {
+            Resolution::Unique(fields) => Some(fields),
+            Resolution::Ambiguous(_) => None,
+        }
+    }
+}
 
-    let own_ticket_values: Vec<u64> = own_ticket
-        .lines()
-        .nth(1)
-        .unwrap()
-        .split(',')
-        .map(|l| l.parse().unwrap())
-        .collect();
-
-    let other_tickets_values: Vec<Vec<u64>> = other_tickets
-        .lines()
-        .skip(1)
-        .map(|line| {
-            line.split(',')
-                .map(|l| l.parse::<u64>().unwrap())
-                .collect::<Vec<u64>>()
-        })
-        .filter(|ticket: &Vec<u64>| {
-            ticket
-                .iter()
-                .all(|number| rules.iter().any(|rule| rule.contains(number)))
+#[derive(Debug, Clone)]
+pub struct Notes {
+    rules: Vec<Rule>,
+    ticket: Vec<u64>,
+    nearby: Vec<Vec<u64>>,
+}
+
+fn parse_ticket(line: &str) -> Result<Vec<u64>, ParseError> {
+    line.split(',')
+        .map(|n| n.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| ParseError::InvalidTicket(line.to_string()))
+}
+
+impl FromStr for Notes {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut sections = input.split("\n\n");
+        let rules = sections
+            .next()
+            .ok_or(ParseError::MissingSection("rules"))?
+            .lines()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        let ticket = sections
+            .next()
+            .and_then(|section| section.lines().nth(1))
+            .ok_or(ParseError::MissingSection("your ticket"))?;
+        let ticket = parse_ticket(ticket)?;
+        let nearby = sections
+            .next()
+            .ok_or(ParseError::MissingSection("nearby tickets"))?
+            .lines()
+            .skip(1)
+            .map(|line| match parse_ticket(line)? {
+                values if values.len() == ticket.len() => Ok(values),
+                _ => Err(ParseError::InvalidTicket(line.to_string())),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Notes {
+            rules,
+            ticket,
+            nearby,
         })
-        .collect();
+    }
+}
+
+impl Notes {
+    pub fn ticket(&self) -> &[u64] {
+        &self.ticket
+    }
 
-    let num_fields = own_ticket_values.len();
+    fn fits_any_rule(&self, n: &u64) -> bool {
+        self.rules.iter().any(|rule| rule.contains(n))
+    }
 
-    let mut guesses: Vec<HashSet<&Rule>> =
-        (0..num_fields).map(|_| rules.iter().collect()).collect();
+    pub fn error_rate(&self) -> u64 {
+        self.nearby
+            .iter()
+            .flatten()
+            .filter(|n| !self.fits_any_rule(n))
+            .sum()
+    }
 
-    for ticket in other_tickets_values {
-        for (col, val) in ticket.iter().enumerate() {
-            let set = guesses.get_mut(col).unwrap();
-            set.retain(|rule| rule.contains(val));
-        }
+    pub fn valid_tickets(&self) -> impl Iterator<Item = &Vec<u64>> {
+        self.nearby
+            .iter()
+            .filter(move |ticket| ticket.iter().all(|n| self.fits_any_rule(n)))
     }
 
-    // At this point we expect that there will be at least 1 set with only one column
-    // We can remove that column from all the other sets.
-    // At that point we expect that there will be other sets with only 1 column, so we keep
-    // removing that column from all the other sets.
-    // We keep going until all the sets are of length 1 (or at least the ones with `departure*`
-    // in the name)
-    let mut sure_columns: HashSet<&Rule> = HashSet::new();
-    let start_column = guesses
-        .iter()
-        .find(|rules| rules.len() == 1)
-        .expect("There needs to be at least 1!");
-    sure_columns.insert(start_column.iter().next().unwrap());
-
-    while sure_columns.len() < guesses.len() {
-        for columns in guesses.iter_mut() {
-            if columns.len() > 1 {
-                for col in sure_columns.iter() {
-                    columns.remove(col);
-                }
-            }
-            if columns.len() == 1 {
-                sure_columns.insert(columns.iter().next().unwrap());
+    pub fn resolve_fields(&self) -> Result<Resolution<'_>, ResolveError> {
+        let columns = self.ticket.len();
+        let valid: Vec<_> = self.valid_tickets().collect();
+        // the fields that fit every column
+        let candidates: Vec<Vec<usize>> = (0..columns)
+            .map(|column| {
+                (0..self.rules.len())
+                    .filter(|field| {
+                        valid
+                            .iter()
+                            .all(|ticket| self.rules[*field].contains(&ticket[column]))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // propagation only prunes the fields a column can't take, the matching decides
+        let mut solver = Solver::new();
+        let vars: Vec<Var> = candidates
+            .iter()
+            .map(|fields| solver.add_variable(fields.iter().copied()))
+            .collect();
+        solver.all_different(vars);
+        let domains: Vec<Vec<usize>> = match solver.narrow() {
+            Ok(domains) => domains
+                .into_iter()
+                .map(|fields| fields.into_iter().collect())
+                .collect(),
+            Err(_) => candidates,
+        };
+
+        let mut graph = BipartiteGraph::new(columns, self.rules.len());
+        for (column, fields) in domains.iter().enumerate() {
+            for field in fields {
+                graph.add_edge(column, *field);
             }
         }
+
+        let names = |matching: Vec<usize>| -> Vec<&str> {
+            matching.into_iter().map(|f| self.rules[f].name()).collect()
+        };
+        let mut matchings = graph.perfect_matchings();
+        match matchings.len() {
+            0 => Err(ResolveError::NoAssignment {
+                matched: graph.maximum_matching().iter().flatten().count(),
+                columns,
+            }),
+            1 => Ok(Resolution::Unique(names(matchings.pop().unwrap()))),
+            _ => Ok(Resolution::Ambiguous(
+                matchings.into_iter().map(names).collect(),
+            )),
+        }
     }
+}
 
-    guesses
+pub fn part1(input: &str) -> u64 {
+    input.parse::<Notes>().unwrap().error_rate()
+}
+
+pub fn part2(input: &str) -> u64 {
+    let notes: Notes = input.parse().unwrap();
+    let fields = notes
+        .resolve_fields()
+        .unwrap()
+        .unique()
+        .expect("The fields are ambiguous");
+
+    fields
         .iter()
-        .map(|s| s.iter().map(|r| &(r.name)).next().unwrap())
-        .enumerate()
-        .filter_map(|(i, name)| match name.starts_with("departure") {
-            true => own_ticket_values.get(i),
-            false => None,
-        })
+        .zip(notes.ticket())
+        .filter(|(name, _)| name.starts_with("departure"))
+        .map(|(_, value)| value)
         .product()
 }
 
@@ -151,6 +225,77 @@ pub fn part2(input: &str) -> u64 {
 mod ex16_tests {
     use super::*;
 
+    const EXAMPLE: &str = "class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,1,5
+5,14,9";
+
+    #[test]
+    fn test_rule() {
+        let rule: Rule = "zone: 1-2 or 5-6 or 9-10".parse().unwrap();
+        assert_eq!(rule.name(), "zone");
+        assert!(rule.contains(&9));
+        assert!(!rule.contains(&7));
+        assert!("seat: 3".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn test_unique() {
+        let notes: Notes = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            notes.resolve_fields(),
+            Ok(Resolution::Unique(vec!["row", "class", "seat"]))
+        );
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let input = EXAMPLE.split("\n15,1,5").next().unwrap();
+        let notes: Notes = input.parse().unwrap();
+        assert_eq!(
+            notes.resolve_fields(),
+            Ok(Resolution::Ambiguous(vec![
+                vec!["row", "class", "seat"],
+                vec!["row", "seat", "class"],
+                vec!["seat", "class", "row"],
+                vec!["seat", "row", "class"],
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_no_assignment() {
+        let input = "class: 0-1
+row: 2-3
+seat: 4-5
+
+your ticket:
+1,2,4
+
+nearby tickets:
+2,2,4";
+        let notes: Notes = input.parse().unwrap();
+        let err = notes.resolve_fields().unwrap_err();
+        assert_eq!(
+            err,
+            ResolveError::NoAssignment {
+                matched: 2,
+                columns: 3
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Only 2 of the 3 columns can be assigned to a distinct field"
+        );
+    }
+
     #[test]
     fn part_1() {
         let input = include_str!("../input.txt");
@@ -0,0 +1,199 @@
+const INF: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+pub struct BipartiteGraph {
+    right: usize,
+    edges: Vec<Vec<usize>>,
+}
+
+impl BipartiteGraph {
+    pub fn new(left: usize, right: usize) -> Self {
+        BipartiteGraph {
+            right,
+            edges: vec![vec![]; left],
+        }
+    }
+
+    pub fn add_edge(&mut self, left: usize, right: usize) {
+        assert!(right < self.right, "right vertex {} out of bounds", right);
+        self.edges[left].push(right);
+    }
+
+    pub fn left(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn right(&self) -> usize {
+        self.right
+    }
+
+    pub fn maximum_matching(&self) -> Vec<Option<usize>> {
+        let mut matcher = Matcher::new(self);
+        matcher.run();
+        matcher.pair_left
+    }
+
+    // every partial choice is checked with a maximum matching before going deeper
+    pub fn perfect_matchings(&self) -> Vec<Vec<usize>> {
+        let mut matcher = Matcher::new(self);
+        let mut matchings = vec![];
+        if matcher.run() == self.left() {
+            self.enumerate(0, &mut matcher, &mut vec![], &mut matchings);
+        }
+        matchings
+    }
+
+    fn enumerate(
+        &self,
+        left: usize,
+        matcher: &mut Matcher,
+        current: &mut Vec<usize>,
+        matchings: &mut Vec<Vec<usize>>,
+    ) {
+        if left == self.left() {
+            matchings.push(current.clone());
+            return;
+        }
+
+        matcher.left_alive[left] = false;
+        for &right in &self.edges[left] {
+            if !matcher.right_alive[right] {
+                continue;
+            }
+            matcher.right_alive[right] = false;
+            if matcher.run() == self.left() - left - 1 {
+                current.push(right);
+                self.enumerate(left + 1, matcher, current, matchings);
+                current.pop();
+            }
+            matcher.right_alive[right] = true;
+        }
+        matcher.left_alive[left] = true;
+    }
+}
+
+// Hopcroft–Karp on the subgraph of the vertices that are still alive
+struct Matcher<'a> {
+    graph: &'a BipartiteGraph,
+    left_alive: Vec<bool>,
+    right_alive: Vec<bool>,
+    pair_left: Vec<Option<usize>>,
+    pair_right: Vec<Option<usize>>,
+    dist: Vec<usize>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(graph: &'a BipartiteGraph) -> Self {
+        Matcher {
+            graph,
+            left_alive: vec![true; graph.left()],
+            right_alive: vec![true; graph.right()],
+            pair_left: vec![None; graph.left()],
+            pair_right: vec![None; graph.right()],
+            dist: vec![INF; graph.left()],
+        }
+    }
+
+    fn run(&mut self) -> usize {
+        self.pair_left.iter_mut().for_each(|pair| *pair = None);
+        self.pair_right.iter_mut().for_each(|pair| *pair = None);
+
+        let mut size = 0;
+        while self.bfs() {
+            for left in 0..self.graph.left() {
+                if self.left_alive[left] && self.pair_left[left].is_none() && self.dfs(left) {
+                    size += 1;
+                }
+            }
+        }
+        size
+    }
+
+    fn bfs(&mut self) -> bool {
+        let mut queue = std::collections::VecDeque::new();
+        for left in 0..self.graph.left() {
+            if self.left_alive[left] && self.pair_left[left].is_none() {
+                self.dist[left] = 0;
+                queue.push_back(left);
+            } else {
+                self.dist[left] = INF;
+            }
+        }
+
+        let mut found = false;
+        while let Some(left) = queue.pop_front() {
+            for &right in &self.graph.edges[left] {
+                if !self.right_alive[right] {
+                    continue;
+                }
+                match self.pair_right[right] {
+                    None => found = true,
+                    Some(next) if self.dist[next] == INF => {
+                        self.dist[next] = self.dist[left] + 1;
+                        queue.push_back(next);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        found
+    }
+
+    fn dfs(&mut self, left: usize) -> bool {
+        for &right in &self.graph.edges[left] {
+            if !self.right_alive[right] {
+                continue;
+            }
+            let augments = match self.pair_right[right] {
+                None => true,
+                Some(next) => self.dist[next] == self.dist[left] + 1 && self.dfs(next),
+            };
+            if augments {
+                self.pair_left[left] = Some(right);
+                self.pair_right[right] = Some(left);
+                return true;
+            }
+        }
+        self.dist[left] = INF;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(right: usize, edges: &[&[usize]]) -> BipartiteGraph {
+        let mut graph = BipartiteGraph::new(edges.len(), right);
+        for (left, rights) in edges.iter().enumerate() {
+            for &r in *rights {
+                graph.add_edge(left, r);
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn test_maximum_matching() {
+        // a greedy choice of 0 -> 0 would leave 1 unmatched
+        let g = graph(2, &[&[0, 1], &[0]]);
+        assert_eq!(g.maximum_matching(), [Some(1), Some(0)]);
+
+        let g = graph(3, &[&[0], &[0], &[1, 2]]);
+        let matching = g.maximum_matching();
+        assert_eq!(matching.iter().flatten().count(), 2);
+        assert!(matching[2].is_some());
+    }
+
+    #[test]
+    fn test_perfect_matchings() {
+        let g = graph(3, &[&[1], &[0, 1, 2], &[0, 2]]);
+        assert_eq!(g.perfect_matchings(), [vec![1, 0, 2], vec![1, 2, 0]]);
+
+        let g = graph(2, &[&[0], &[0]]);
+        assert!(g.perfect_matchings().is_empty());
+
+        let g = graph(3, &[&[0, 1, 2], &[0, 1, 2], &[0, 1, 2]]);
+        assert_eq!(g.perfect_matchings().len(), 6);
+    }
+}
@@ -1,10 +1,16 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use ex17::{part1, part2};
+use ex17::{part1, part2, Life, LifeConfig};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let input = include_str!("../input.txt");
     c.bench_function("ex17::part1", |b| b.iter(|| part1(black_box(input))));
     c.bench_function("ex17::part2", |b| b.iter(|| part2(black_box(input))));
+    c.bench_function("ex17::5d", |b| {
+        b.iter(|| Life::<5>::from_input(black_box(input), LifeConfig::default()).nth(5))
+    });
+    c.bench_function("ex17::6d", |b| {
+        b.iter(|| Life::<6>::from_input(black_box(input), LifeConfig::default()).nth(5))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,225 @@
+use crate::neighbourhood::Neighbourhood;
+use crate::rule::Rule;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Default)]
+pub struct SparseCells<const D: usize>(pub HashSet<[i32; D]>);
+
+impl<const D: usize> SparseCells<D> {
+    pub fn contains(&self, point: &[i32; D]) -> bool {
+        self.0.contains(point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = [i32; D]> + '_ {
+        self.0.iter().copied()
+    }
+
+    pub fn step(&self, neighbourhood: &Neighbourhood<D>, rule: &Rule) -> Self {
+        let mut counts: HashMap<[i32; D], u32> =
+            HashMap::with_capacity(self.len() * neighbourhood.offsets().len());
+
+        for point in self.iter() {
+            counts.entry(point).or_insert(0);
+            let weight = neighbourhood.weight(&point);
+            neighbourhood.for_each_neighbour(&point, |neighbour| {
+                *counts.entry(neighbour).or_insert(0) += weight;
+            });
+        }
+
+        SparseCells(
+            counts
+                .into_iter()
+                .filter(|(point, count)| {
+                    let active_neighbours = count / neighbourhood.weight(point);
+                    rule.next_state(self.contains(point), active_neighbours)
+                })
+                .map(|(point, _)| point)
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DenseCells<const D: usize> {
+    min: [i32; D],
+    dims: [usize; D],
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl<const D: usize> DenseCells<D> {
+    pub fn from_points(points: impl Iterator<Item = [i32; D]> + Clone) -> Self {
+        let (min, dims) = match bounding_box(points.clone()) {
+            Some((min, max)) => (min, dims_of(&min, &max)),
+            None => ([0; D], [0; D]),
+        };
+        let mut cells = DenseCells::empty(min, dims);
+        for point in points {
+            cells.insert(&point);
+        }
+        cells
+    }
+
+    fn empty(min: [i32; D], dims: [usize; D]) -> Self {
+        let volume: usize = dims.iter().product();
+        DenseCells {
+            min,
+            dims,
+            bits: vec![0; volume.div_ceil(64)],
+            len: 0,
+        }
+    }
+
+    pub fn volume(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    fn index_of(&self, point: &[i32; D]) -> Option<usize> {
+        let mut index = 0;
+        for axis in (0..D).rev() {
+            let coord = point[axis] - self.min[axis];
+            if coord < 0 || coord as usize >= self.dims[axis] {
+                return None;
+            }
+            index = index * self.dims[axis] + coord as usize;
+        }
+        Some(index)
+    }
+
+    fn point_at(&self, mut index: usize) -> [i32; D] {
+        let mut point = self.min;
+        for (axis, coord) in point.iter_mut().enumerate() {
+            *coord += (index % self.dims[axis]) as i32;
+            index /= self.dims[axis];
+        }
+        point
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn insert(&mut self, point: &[i32; D]) {
+        let index = self
+            .index_of(point)
+            .expect("Point outside of the dense bounding box");
+        if !self.get(index) {
+            self.bits[index / 64] |= 1 << (index % 64);
+            self.len += 1;
+        }
+    }
+
+    pub fn contains(&self, point: &[i32; D]) -> bool {
+        self.index_of(point).is_some_and(|index| self.get(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = [i32; D]> + Clone + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+            .map(move |index| self.point_at(index))
+    }
+
+    pub fn step(&self, neighbourhood: &Neighbourhood<D>, rule: &Rule) -> Self {
+        if self.len == 0 {
+            return self.clone();
+        }
+
+        let radius = neighbourhood.radius() as i32;
+        let mut min = self.min;
+        let mut max = self.min;
+        for axis in 0..D {
+            max[axis] += self.dims[axis] as i32 - 1 + radius;
+            min[axis] -= radius;
+        }
+        neighbourhood.clamp_to_canonical(&mut min);
+        let dims = dims_of(&min, &max);
+
+        let area = DenseCells::<D>::empty(min, dims);
+        let mut neighbour_counts = vec![0_u32; area.volume()];
+        for point in self.iter() {
+            let weight = neighbourhood.weight(&point);
+            neighbourhood.for_each_neighbour(&point, |neighbour| {
+                let index = area
+                    .index_of(&neighbour)
+                    .expect("Canonical neighbours always fall inside the expanded box");
+                neighbour_counts[index] += weight;
+            });
+        }
+
+        let mut next_points = vec![];
+        for (index, count) in neighbour_counts.into_iter().enumerate() {
+            let point = area.point_at(index);
+            let active_neighbours = count / neighbourhood.weight(&point);
+            if rule.next_state(self.contains(&point), active_neighbours) {
+                next_points.push(point);
+            }
+        }
+        // shrink the bounding box back to the cells that are actually alive
+        DenseCells::from_points(next_points.into_iter())
+    }
+}
+
+fn dims_of<const D: usize>(min: &[i32; D], max: &[i32; D]) -> [usize; D] {
+    let mut dims = [0; D];
+    for axis in 0..D {
+        dims[axis] = (max[axis] - min[axis] + 1) as usize;
+    }
+    dims
+}
+
+pub fn bounding_box<const D: usize>(
+    points: impl Iterator<Item = [i32; D]>,
+) -> Option<([i32; D], [i32; D])> {
+    points.fold(None, |bounds, point| {
+        let (mut min, mut max) = bounds.unwrap_or((point, point));
+        for axis in 0..D {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+        Some((min, max))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense_indexing() {
+        let points = [[0, 0, 0], [2, -1, 3], [1, 1, 1]];
+        let cells = DenseCells::<3>::from_points(points.iter().copied());
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells.volume(), 3 * 3 * 4);
+        for point in points {
+            assert!(cells.contains(&point));
+        }
+        assert!(!cells.contains(&[1, 0, 0]));
+        assert!(!cells.contains(&[10, 0, 0]));
+
+        let mut found: Vec<_> = cells.iter().collect();
+        found.sort();
+        let mut expected = points.to_vec();
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+}
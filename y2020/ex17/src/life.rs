@@ -0,0 +1,260 @@
+use crate::backend::{bounding_box, DenseCells, SparseCells};
+use crate::neighbourhood::Neighbourhood;
+use crate::rule::Rule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Auto,
+    Sparse,
+    Dense,
+}
+
+#[derive(Debug, Clone)]
+pub struct LifeConfig {
+    pub radius: u32,
+    pub rule: Rule,
+    pub backend: Backend,
+    pub dense_threshold: f64,
+    pub symmetry: bool,
+}
+
+impl Default for LifeConfig {
+    fn default() -> Self {
+        LifeConfig {
+            radius: 1,
+            rule: Rule::default(),
+            backend: Backend::Auto,
+            dense_threshold: 0.1,
+            symmetry: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Cells<const D: usize> {
+    Sparse(SparseCells<D>),
+    Dense(DenseCells<D>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Life<const D: usize> {
+    cells: Cells<D>,
+    neighbourhood: Neighbourhood<D>,
+    config: LifeConfig,
+    generation: usize,
+}
+
+impl<const D: usize> Life<D> {
+    pub fn from_input(input: &str, config: LifeConfig) -> Self {
+        assert!(
+            D >= 2,
+            "The seed is 2D, so at least 2 dimensions are needed"
+        );
+        assert!(
+            config.radius > 0,
+            "The neighbourhood radius must be positive"
+        );
+
+        let mut data = vec![];
+        for (y, line) in input.lines().enumerate() {
+            for (x, cell) in line.chars().enumerate() {
+                if cell == '#' {
+                    let mut point = [0; D];
+                    point[0] = x as i32;
+                    point[1] = y as i32;
+                    data.push(point);
+                }
+            }
+        }
+
+        let mut life = Life {
+            cells: Cells::Sparse(SparseCells(data.into_iter().collect())),
+            neighbourhood: Neighbourhood::new(config.radius, config.symmetry),
+            config,
+            generation: 0,
+        };
+        life.pick_backend();
+        life
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self.cells {
+            Cells::Sparse(_) => Backend::Sparse,
+            Cells::Dense(_) => Backend::Dense,
+        }
+    }
+
+    pub fn is_active(&self, point: [i32; D]) -> bool {
+        let point = self.neighbourhood.canonical(point);
+        match &self.cells {
+            Cells::Sparse(cells) => cells.contains(&point),
+            Cells::Dense(cells) => cells.contains(&point),
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.stored_cells()
+            .iter()
+            .map(|point| self.neighbourhood.weight(point) as usize)
+            .sum()
+    }
+
+    fn stored_cells(&self) -> Vec<[i32; D]> {
+        match &self.cells {
+            Cells::Sparse(cells) => cells.iter().collect(),
+            Cells::Dense(cells) => cells.iter().collect(),
+        }
+    }
+
+    fn stored_len(&self) -> usize {
+        match &self.cells {
+            Cells::Sparse(cells) => cells.len(),
+            Cells::Dense(cells) => cells.len(),
+        }
+    }
+
+    fn pick_backend(&mut self) {
+        let wanted = match self.config.backend {
+            Backend::Auto => {
+                let points = self.stored_cells();
+                let volume: usize = match bounding_box(points.iter().copied()) {
+                    Some((min, max)) => min
+                        .iter()
+                        .zip(max.iter())
+                        .map(|(min, max)| (max - min + 1) as usize)
+                        .product(),
+                    None => 1,
+                };
+                if self.stored_len() as f64 / volume as f64 >= self.config.dense_threshold {
+                    Backend::Dense
+                } else {
+                    Backend::Sparse
+                }
+            }
+            backend => backend,
+        };
+
+        if wanted != self.backend() {
+            let points = self.stored_cells();
+            self.cells = match wanted {
+                Backend::Dense => Cells::Dense(DenseCells::from_points(points.into_iter())),
+                _ => Cells::Sparse(SparseCells(points.into_iter().collect())),
+            }
+        }
+    }
+}
+
+impl<const D: usize> Iterator for Life<D> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cells = match &self.cells {
+            Cells::Sparse(cells) => {
+                Cells::Sparse(cells.step(&self.neighbourhood, &self.config.rule))
+            }
+            Cells::Dense(cells) => Cells::Dense(cells.step(&self.neighbourhood, &self.config.rule)),
+        };
+        self.generation += 1;
+        self.pick_backend();
+        Some(self.population())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ".#.\n..#\n###";
+
+    fn populations<const D: usize>(input: &str, config: LifeConfig, cycles: usize) -> Vec<usize> {
+        Life::<D>::from_input(input, config).take(cycles).collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let mut life = Life::<3>::from_input(EXAMPLE, LifeConfig::default());
+        assert_eq!(life.population(), 5);
+        assert_eq!(life.nth(5), Some(112));
+        assert_eq!(life.generation(), 6);
+
+        let mut life = Life::<4>::from_input(EXAMPLE, LifeConfig::default());
+        assert_eq!(life.nth(5), Some(848));
+    }
+
+    #[test]
+    fn test_backends_and_symmetry_agree() {
+        let input = include_str!("../input.txt");
+        let mut results = vec![];
+        for backend in [Backend::Sparse, Backend::Dense, Backend::Auto] {
+            for symmetry in [false, true] {
+                let config = LifeConfig {
+                    backend,
+                    symmetry,
+                    ..Default::default()
+                };
+                results.push(populations::<4>(input, config, 4));
+            }
+        }
+        assert!(results.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+
+    #[test]
+    fn test_is_active_mirrors() {
+        let mut life = Life::<4>::from_input(EXAMPLE, LifeConfig::default());
+        life.next();
+        // mirror images along z and w, and swapping z with w, are active together
+        assert!(life.is_active([0, 1, -1, 0]));
+        assert!(life.is_active([0, 1, 1, 0]));
+        assert!(life.is_active([0, 1, 0, 1]));
+        assert!(life.is_active([0, 1, 0, -1]));
+        assert!(!life.is_active([1, 1, 1, 0]));
+    }
+
+    #[test]
+    fn test_custom_rule_and_radius() {
+        // a blinker oscillates in plain 2D game of life
+        let blinker = "...\n###\n...";
+        assert_eq!(
+            populations::<2>(blinker, LifeConfig::default(), 3),
+            [3, 3, 3]
+        );
+
+        let mut life = Life::<2>::from_input(blinker, LifeConfig::default());
+        life.next();
+        assert!(life.is_active([1, 0]));
+        assert!(life.is_active([1, 2]));
+        assert!(!life.is_active([0, 1]));
+
+        // with no survival every cell dies after one cycle
+        let config = LifeConfig {
+            rule: "B9/S".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(populations::<3>(EXAMPLE, config, 1), [0]);
+
+        // radius 2 in 2D: every cell of the 3x3 block has 8 active neighbours
+        let config = LifeConfig {
+            radius: 2,
+            rule: "B/S8".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(populations::<2>("###\n###\n###", config, 2), [9, 9]);
+    }
+
+    #[test]
+    fn test_auto_backend_switches() {
+        let crowded = Life::<3>::from_input("###\n###", LifeConfig::default());
+        assert_eq!(crowded.backend(), Backend::Dense);
+
+        let config = LifeConfig {
+            dense_threshold: 1.1,
+            ..Default::default()
+        };
+        let sparse = Life::<3>::from_input("###\n###", config);
+        assert_eq!(sparse.backend(), Backend::Sparse);
+    }
+}
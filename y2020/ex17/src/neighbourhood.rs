@@ -0,0 +1,166 @@
+// with symmetry, only the canonical cells (extra coordinates non-negative and sorted)
+// are stored
+#[derive(Debug, Clone)]
+pub struct Neighbourhood<const D: usize> {
+    radius: u32,
+    offsets: Vec<[i32; D]>,
+    symmetry: bool,
+    factorials: Vec<u32>,
+}
+
+impl<const D: usize> Neighbourhood<D> {
+    pub fn new(radius: u32, symmetry: bool) -> Self {
+        let factorials = (0..=D as u32)
+            .scan(1, |acc, n| {
+                *acc *= n.max(1);
+                Some(*acc)
+            })
+            .collect();
+
+        Neighbourhood {
+            radius,
+            offsets: relative_neighbours(radius),
+            symmetry: symmetry && D > 2,
+            factorials,
+        }
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    pub fn offsets(&self) -> &[[i32; D]] {
+        &self.offsets
+    }
+
+    pub fn canonical(&self, mut point: [i32; D]) -> [i32; D] {
+        if self.symmetry {
+            let extra = &mut point[2..];
+            for coord in extra.iter_mut() {
+                *coord = coord.abs();
+            }
+            extra.sort_unstable();
+        }
+        point
+    }
+
+    pub fn clamp_to_canonical(&self, min: &mut [i32; D]) {
+        if self.symmetry {
+            for coord in min[2..].iter_mut() {
+                *coord = (*coord).max(0);
+            }
+        }
+    }
+
+    pub fn weight(&self, point: &[i32; D]) -> u32 {
+        if !self.symmetry {
+            return 1;
+        }
+
+        let extra = &point[2..];
+        let mirrors = 1 << extra.iter().filter(|c| **c != 0).count();
+        let mut permutations = self.factorials[extra.len()];
+        let mut run = 1;
+        for pair in extra.windows(2) {
+            if pair[0] == pair[1] {
+                run += 1;
+            } else {
+                permutations /= self.factorials[run];
+                run = 1;
+            }
+        }
+        permutations /= self.factorials[run];
+
+        mirrors * permutations
+    }
+
+    // a neighbour reached from `point` gets weight(point) per hit: dividing its total by its
+    // own weight gives the active neighbours of any of the cells it stands for
+    pub fn for_each_neighbour(&self, point: &[i32; D], mut f: impl FnMut([i32; D])) {
+        for offset in &self.offsets {
+            let mut neighbour = *point;
+            for (coord, delta) in neighbour.iter_mut().zip(offset) {
+                *coord += delta;
+            }
+            f(self.canonical(neighbour));
+        }
+    }
+}
+
+pub fn relative_neighbours<const D: usize>(radius: u32) -> Vec<[i32; D]> {
+    let side = 2 * radius as i32 + 1;
+    let mut relative_neighbours = vec![];
+
+    for i in 0..side.pow(D as u32) {
+        let mut relative_coords = [0; D];
+        let mut n = i;
+        for coord in relative_coords.iter_mut() {
+            *coord = (n % side) - radius as i32;
+            n /= side;
+        }
+        if relative_coords != [0; D] {
+            relative_neighbours.push(relative_coords);
+        }
+    }
+
+    relative_neighbours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(
+            relative_neighbours::<3>(1),
+            [
+                [-1, -1, -1],
+                [0, -1, -1],
+                [1, -1, -1],
+                [-1, 0, -1],
+                [0, 0, -1],
+                [1, 0, -1],
+                [-1, 1, -1],
+                [0, 1, -1],
+                [1, 1, -1],
+                [-1, -1, 0],
+                [0, -1, 0],
+                [1, -1, 0],
+                [-1, 0, 0],
+                [1, 0, 0],
+                [-1, 1, 0],
+                [0, 1, 0],
+                [1, 1, 0],
+                [-1, -1, 1],
+                [0, -1, 1],
+                [1, -1, 1],
+                [-1, 0, 1],
+                [0, 0, 1],
+                [1, 0, 1],
+                [-1, 1, 1],
+                [0, 1, 1],
+                [1, 1, 1],
+            ]
+        );
+        assert_eq!(relative_neighbours::<2>(2).len(), 24);
+        assert_eq!(relative_neighbours::<4>(1).len(), 80);
+    }
+
+    #[test]
+    fn test_canonical_and_weight() {
+        let neighbourhood = Neighbourhood::<5>::new(1, true);
+        assert_eq!(neighbourhood.canonical([1, 2, -3, 0, 2]), [1, 2, 0, 2, 3]);
+        assert_eq!(neighbourhood.weight(&[1, 2, 0, 0, 0]), 1);
+        // (0, 0, ±1) in any order
+        assert_eq!(neighbourhood.weight(&[1, 2, 0, 0, 1]), 6);
+        // (±1, ±1, ±2) in any order
+        assert_eq!(neighbourhood.weight(&[1, 2, 1, 1, 2]), 24);
+        // all the permutations of (±1, ±2, ±3)
+        assert_eq!(neighbourhood.weight(&[1, 2, 1, 2, 3]), 48);
+
+        let plain = Neighbourhood::<5>::new(1, false);
+        assert_eq!(plain.canonical([1, 2, -3, 0, 2]), [1, 2, -3, 0, 2]);
+        assert_eq!(plain.weight(&[1, 2, 1, 2, 3]), 1);
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    #[error("Rule must have the form B<counts>/S<counts>, found '{0}'")]
+    InvalidFormat(String),
+    #[error("Invalid neighbour count '{0}'")]
+    InvalidCount(String),
+    #[error("B0 rules are not supported: they would activate the whole infinite space")]
+    BirthOnZero,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: Vec<bool>,
+    survival: Vec<bool>,
+}
+
+impl Rule {
+    pub fn next_state(&self, alive: bool, active_neighbours: u32) -> bool {
+        let counts = if alive { &self.survival } else { &self.birth };
+        counts
+            .get(active_neighbours as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+}
+
+fn parse_counts(s: &str) -> Result<Vec<bool>, RuleParseError> {
+    let mut counts: Vec<usize> = vec![];
+
+    if s.contains(',') || s.contains('-') {
+        for part in s.split(',') {
+            let invalid = || RuleParseError::InvalidCount(part.to_string());
+            match part.split_once('-') {
+                Some((from, to)) => {
+                    let from: usize = from.parse().map_err(|_| invalid())?;
+                    let to: usize = to.parse().map_err(|_| invalid())?;
+                    if from > to {
+                        return Err(invalid());
+                    }
+                    counts.extend(from..=to);
+                }
+                None => counts.push(part.parse().map_err(|_| invalid())?),
+            }
+        }
+    } else {
+        for c in s.chars() {
+            let count = c
+                .to_digit(10)
+                .ok_or_else(|| RuleParseError::InvalidCount(c.to_string()))?;
+            counts.push(count as usize);
+        }
+    }
+
+    let mut flags = vec![false; counts.iter().max().map_or(0, |max| max + 1)];
+    for count in counts {
+        flags[count] = true;
+    }
+    Ok(flags)
+}
+
+fn format_counts(flags: &[bool]) -> String {
+    let counts: Vec<usize> = flags
+        .iter()
+        .enumerate()
+        .filter_map(|(count, active)| active.then_some(count))
+        .collect();
+
+    if counts.iter().all(|c| *c < 10) {
+        counts.iter().map(|c| c.to_string()).collect()
+    } else {
+        counts
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RuleParseError::InvalidFormat(s.to_string());
+        let (birth, survival) = s.split_once('/').ok_or_else(invalid)?;
+        let birth = birth
+            .strip_prefix('B')
+            .or_else(|| birth.strip_prefix('b'))
+            .ok_or_else(invalid)?;
+        let survival = survival
+            .strip_prefix('S')
+            .or_else(|| survival.strip_prefix('s'))
+            .ok_or_else(invalid)?;
+
+        let birth = parse_counts(birth)?;
+        if birth.first() == Some(&true) {
+            return Err(RuleParseError::BirthOnZero);
+        }
+
+        Ok(Rule {
+            birth,
+            survival: parse_counts(survival)?,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "B{}/S{}",
+            format_counts(&self.birth),
+            format_counts(&self.survival)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let rule: Rule = "B3/S23".parse().unwrap();
+        assert!(rule.next_state(false, 3));
+        assert!(!rule.next_state(false, 2));
+        assert!(rule.next_state(true, 2));
+        assert!(rule.next_state(true, 3));
+        assert!(!rule.next_state(true, 4));
+        assert!(!rule.next_state(true, 100));
+        assert_eq!(rule, Rule::default());
+    }
+
+    #[test]
+    fn test_parse_lists_and_ranges() {
+        let rule: Rule = "B3,10-12/S2,3".parse().unwrap();
+        assert!(rule.next_state(false, 10));
+        assert!(rule.next_state(false, 12));
+        assert!(!rule.next_state(false, 13));
+        assert_eq!(rule.to_string(), "B3,10,11,12/S23");
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for s in ["B3/S23", "B36/S23", "B2/S", "B1357/S02468"] {
+            let rule: Rule = s.parse().unwrap();
+            assert_eq!(rule.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            "3/23".parse::<Rule>(),
+            Err(RuleParseError::InvalidFormat("3/23".to_string()))
+        );
+        assert_eq!(
+            "B3/S2x".parse::<Rule>(),
+            Err(RuleParseError::InvalidCount("x".to_string()))
+        );
+        assert_eq!(
+            "B5-2/S2".parse::<Rule>(),
+            Err(RuleParseError::InvalidCount("5-2".to_string()))
+        );
+        assert_eq!("B03/S23".parse::<Rule>(), Err(RuleParseError::BirthOnZero));
+    }
+}
@@ -1,36 +1,78 @@
-use std::collections::{HashMap, HashSet};
+pub use csp::{SolveError, Solver, Var};
+use std::collections::{BTreeMap, HashSet};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AllergenError {
+    #[error("Invalid food on line {0}")]
+    InvalidFood(usize),
+    #[error("Can't tell which ingredient contains each allergen: {0}")]
+    Unsolvable(#[from] SolveError),
+}
 
 struct AllergensIngredients<'a> {
-    mapping: HashMap<&'a str, HashSet<&'a str>>,
+    mapping: BTreeMap<&'a str, HashSet<&'a str>>,
     ingredients: Vec<&'a str>,
 }
 
-impl<'a> From<&'a str> for AllergensIngredients<'a> {
-    fn from(input: &'a str) -> Self {
-        let mut mapping: HashMap<&'a str, HashSet<&'a str>> = Default::default();
+impl<'a> TryFrom<&'a str> for AllergensIngredients<'a> {
+    type Error = AllergenError;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let mut mapping: BTreeMap<&'a str, HashSet<&'a str>> = Default::default();
         let mut ingredients: Vec<&str> = Default::default();
 
-        input.lines().for_each(|line| {
-            let (ing, alle) = line[..(line.len() - 1)].split_once(" (contains ").unwrap();
+        for (i, line) in input.lines().enumerate() {
+            let (ing, alle) = line
+                .strip_suffix(')')
+                .and_then(|line| line.split_once(" (contains "))
+                .ok_or(AllergenError::InvalidFood(i + 1))?;
             let ings: HashSet<&str> = ing.split(' ').collect();
-            let alles: HashSet<&str> = alle.split(", ").collect();
             ingredients.extend(ings.iter());
-            for allergen in alles {
+            for allergen in alle.split(", ") {
                 let values = mapping.entry(allergen).or_insert_with(|| ings.clone());
-                let new_values: HashSet<&str> = values.intersection(&ings).cloned().collect();
-                mapping.insert(allergen, new_values);
+                values.retain(|ing| ings.contains(ing));
             }
-        });
+        }
 
-        Self {
+        Ok(Self {
             mapping,
             ingredients,
-        }
+        })
+    }
+}
+
+impl<'a> AllergensIngredients<'a> {
+    fn resolve(&self) -> Result<Vec<(&'a str, &'a str)>, AllergenError> {
+        let mut candidates: Vec<&str> = self.mapping.values().flatten().copied().collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut solver = Solver::new();
+        let vars: Vec<Var> = self
+            .mapping
+            .values()
+            .map(|ings| {
+                solver.add_variable(
+                    ings.iter()
+                        .map(|ing| candidates.binary_search(ing).unwrap()),
+                )
+            })
+            .collect();
+        solver.all_different(vars);
+
+        let solution = solver.solve()?;
+        Ok(self
+            .mapping
+            .keys()
+            .zip(solution)
+            .map(|(allergen, ing)| (*allergen, candidates[ing]))
+            .collect())
     }
 }
 
 pub fn part1(input: &str) -> usize {
-    let allergens_ingredients: AllergensIngredients = input.into();
+    let allergens_ingredients = AllergensIngredients::try_from(input).unwrap();
 
     let bad_ingredients_list: HashSet<&str> = allergens_ingredients
         .mapping
@@ -46,35 +88,61 @@ pub fn part1(input: &str) -> usize {
         .count()
 }
 
-pub fn part2(input: &str) -> String {
-    let mut allergens_ingredients: AllergensIngredients = input.into();
-    let mut sure_mappings: HashMap<&str, &str> = Default::default();
-
-    while sure_mappings.len() != allergens_ingredients.mapping.len() {
-        for (all, ings) in &allergens_ingredients.mapping {
-            if ings.len() == 1 {
-                sure_mappings.insert(all, ings.iter().next().unwrap());
-            }
-        }
-        sure_mappings.iter().for_each(|(_, ing)| {
-            for (_, ings_set) in allergens_ingredients.mapping.iter_mut() {
-                if ings_set.len() > 1 {
-                    ings_set.remove(ing);
-                }
-            }
-        });
-    }
+pub fn dangerous_ingredients(input: &str) -> Result<String, AllergenError> {
+    let allergens_ingredients = AllergensIngredients::try_from(input)?;
+    let resolved = allergens_ingredients.resolve()?;
 
-    let mut ret: Vec<(&str, &str)> = sure_mappings.into_iter().collect();
-    ret.sort_by_key(|x| x.0);
+    Ok(resolved
+        .iter()
+        .map(|x| x.1)
+        .collect::<Vec<&str>>()
+        .join(","))
+}
 
-    ret.iter().map(|x| x.1).collect::<Vec<&str>>().join(",")
+pub fn part2(input: &str) -> String {
+    dangerous_ingredients(input).unwrap()
 }
 
 #[cfg(test)]
 mod ex21_tests {
     use super::*;
 
+    const EXAMPLE: &str = "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+trh fvjkl sbzzf mxmxvkd (contains dairy)
+sqjhc fvjkl (contains soy)
+sqjhc mxmxvkd sbzzf (contains fish)";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(EXAMPLE), 5);
+        assert_eq!(
+            dangerous_ingredients(EXAMPLE),
+            Ok(String::from("mxmxvkd,sqjhc,fvjkl"))
+        );
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        // nothing tells apart the ingredients of dairy and fish
+        let input = "a b (contains dairy, fish)";
+        assert!(matches!(
+            dangerous_ingredients(input),
+            Err(AllergenError::Unsolvable(SolveError::Ambiguous { .. }))
+        ));
+
+        // two allergens, but a single ingredient that could contain them
+        let input = "a b (contains dairy)\na c (contains fish)\na d (contains dairy, fish)";
+        assert_eq!(
+            dangerous_ingredients(input).unwrap_err().to_string(),
+            "Can't tell which ingredient contains each allergen: The constraints can't be satisfied"
+        );
+
+        assert_eq!(
+            dangerous_ingredients("a b (contains dairy)\na b"),
+            Err(AllergenError::InvalidFood(2))
+        );
+    }
+
     #[test]
     fn part_1() {
         let input = include_str!("../input.txt");
@@ -0,0 +1,293 @@
+use crate::log::{Event, GameLog};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+use thiserror::Error;
+
+pub type Card = u8;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DeckParseError {
+    #[error("Invalid card '{0}': cards go from 1 to 255")]
+    InvalidCard(String),
+}
+
+#[derive(Hash, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Deck(VecDeque<Card>);
+
+impl Deref for Deck {
+    type Target = VecDeque<Card>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Deck {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Deck {
+    pub fn calculate_score(&self) -> usize {
+        self.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, n)| (i + 1) * *n as usize)
+            .sum()
+    }
+}
+
+impl FromIterator<Card> for Deck {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        Deck(iter.into_iter().collect())
+    }
+}
+
+impl FromStr for Deck {
+    type Err = DeckParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .skip(1)
+            .map(|i| match i.parse::<Card>() {
+                Ok(card) if card > 0 => Ok(card),
+                _ => Err(DeckParseError::InvalidCard(i.to_string())),
+            })
+            .collect()
+    }
+}
+
+impl Display for Deck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cards: Vec<String> = self.iter().map(|card| card.to_string()).collect();
+        write!(f, "{}", cards.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Player::One => write!(f, "1"),
+            Player::Two => write!(f, "2"),
+        }
+    }
+}
+
+// cards are never 0, so a 0 can split the two decks
+type State = Box<[u8]>;
+
+fn encode(deck1: &Deck, deck2: &Deck) -> State {
+    deck1
+        .iter()
+        .chain(&[0])
+        .chain(deck2.iter())
+        .copied()
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct RecursiveCombat {
+    memoise: bool,
+    winners: HashMap<State, Player>,
+    games: usize,
+    recalled: usize,
+    log: Option<Vec<Event>>,
+}
+
+impl Default for RecursiveCombat {
+    fn default() -> Self {
+        RecursiveCombat {
+            memoise: true,
+            winners: HashMap::new(),
+            games: 0,
+            recalled: 0,
+            log: None,
+        }
+    }
+}
+
+impl RecursiveCombat {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn without_memo(self) -> Self {
+        RecursiveCombat {
+            memoise: false,
+            ..self
+        }
+    }
+
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    pub fn recalled(&self) -> usize {
+        self.recalled
+    }
+
+    pub fn play(&mut self, deck1: Deck, deck2: Deck) -> (Player, Deck) {
+        let (winner, deck1, deck2) = self.game(deck1, deck2, 0);
+        match winner {
+            Player::One => (winner, deck1),
+            Player::Two => (winner, deck2),
+        }
+    }
+
+    pub fn play_logged(&mut self, deck1: Deck, deck2: Deck) -> GameLog {
+        self.log = Some(vec![]);
+        let decks = [deck1.clone(), deck2.clone()];
+        self.play(deck1, deck2);
+        GameLog::new(decks, self.log.take().unwrap_or_default())
+    }
+
+    fn record(&mut self, event: impl FnOnce() -> Event) {
+        if let Some(log) = &mut self.log {
+            log.push(event());
+        }
+    }
+
+    fn game(&mut self, mut deck1: Deck, mut deck2: Deck, depth: usize) -> (Player, Deck, Deck) {
+        self.games += 1;
+        let game = self.games;
+        self.record(|| Event::GameStarted { game, depth });
+
+        let mut history: HashSet<State> = HashSet::new();
+        let mut round = 0;
+        let winner = loop {
+            round += 1;
+            // if decks had the same state in other rounds, P1 instantly wins
+            if !history.insert(encode(&deck1, &deck2)) {
+                self.record(|| Event::StateRepeated { game, depth, round });
+                break Player::One;
+            }
+            if deck1.is_empty() {
+                break Player::Two;
+            }
+            if deck2.is_empty() {
+                break Player::One;
+            }
+
+            self.record(|| Event::CardsPlayed {
+                game,
+                depth,
+                round,
+                decks: [deck1.clone(), deck2.clone()],
+                cards: [deck1[0], deck2[0]],
+            });
+            let p1_card = deck1.pop_front().unwrap();
+            let p2_card = deck2.pop_front().unwrap();
+
+            let round_winner = if p1_card as usize <= deck1.len() && p2_card as usize <= deck2.len()
+            {
+                // recursive combat game initiated here
+                let sub_deck1: Deck = deck1.iter().take(p1_card as usize).copied().collect();
+                let sub_deck2: Deck = deck2.iter().take(p2_card as usize).copied().collect();
+                self.sub_game(sub_deck1, sub_deck2, depth + 1)
+            } else if p1_card > p2_card {
+                Player::One
+            } else {
+                Player::Two
+            };
+
+            match round_winner {
+                Player::One => deck1.extend([p1_card, p2_card]),
+                Player::Two => deck2.extend([p2_card, p1_card]),
+            }
+            self.record(|| Event::RoundWon {
+                game,
+                depth,
+                round,
+                winner: round_winner,
+            });
+        };
+
+        self.record(|| Event::GameWon {
+            game,
+            depth,
+            winner,
+        });
+        (winner, deck1, deck2)
+    }
+
+    fn sub_game(&mut self, deck1: Deck, deck2: Deck, depth: usize) -> Player {
+        if !self.memoise {
+            return self.game(deck1, deck2, depth).0;
+        }
+
+        let state = encode(&deck1, &deck2);
+        if let Some(&winner) = self.winners.get(&state) {
+            self.recalled += 1;
+            self.record(|| Event::SubGameRecalled { depth, winner });
+            return winner;
+        }
+        let (winner, _, _) = self.game(deck1, deck2, depth);
+        self.winners.insert(state, winner);
+        winner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_state() {
+        // both used to get the digest 61, making these two different rounds look the same
+        let a: Deck = "Player 1:\n61".parse().unwrap();
+        let b: Deck = "Player 1:\n1\n2".parse().unwrap();
+        let empty = Deck::default();
+        assert_ne!(encode(&a, &empty), encode(&b, &empty));
+
+        // moving a card from a deck to the other is a different state
+        let c: Deck = [1, 2].into_iter().collect();
+        let d: Deck = [3].into_iter().collect();
+        let e: Deck = [1].into_iter().collect();
+        let f: Deck = [2, 3].into_iter().collect();
+        assert_eq!(&*encode(&c, &d), [1, 2, 0, 3]);
+        assert_ne!(encode(&c, &d), encode(&e, &f));
+
+        let mut history: HashSet<State> = HashSet::new();
+        history.insert(encode(&c, &d));
+        assert!(history.contains(&encode(&c.clone(), &d.clone())));
+    }
+
+    #[test]
+    fn test_invalid_deck() {
+        assert_eq!(
+            "Player 1:\n0".parse::<Deck>(),
+            Err(DeckParseError::InvalidCard("0".to_string()))
+        );
+        assert_eq!(
+            "Player 1:\n256".parse::<Deck>(),
+            Err(DeckParseError::InvalidCard("256".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_memoisation() {
+        let input = include_str!("../input.txt");
+        let (p1, p2) = input.split_once("\n\n").unwrap();
+        let (deck1, deck2): (Deck, Deck) = (p1.parse().unwrap(), p2.parse().unwrap());
+
+        let mut memoised = RecursiveCombat::new();
+        let (winner, deck) = memoised.play(deck1.clone(), deck2.clone());
+        let mut plain = RecursiveCombat::new().without_memo();
+        assert_eq!(plain.play(deck1, deck2), (winner, deck));
+
+        assert!(memoised.recalled() > 0);
+        assert_eq!(plain.recalled(), 0);
+        assert!(memoised.games() < plain.games());
+    }
+}
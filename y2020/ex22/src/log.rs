@@ -0,0 +1,291 @@
+use crate::combat::{Card, Deck, Player};
+use std::fmt::Display;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    GameStarted {
+        game: usize,
+        depth: usize,
+    },
+    CardsPlayed {
+        game: usize,
+        depth: usize,
+        round: usize,
+        decks: [Deck; 2],
+        cards: [Card; 2],
+    },
+    SubGameRecalled {
+        depth: usize,
+        winner: Player,
+    },
+    RoundWon {
+        game: usize,
+        depth: usize,
+        round: usize,
+        winner: Player,
+    },
+    StateRepeated {
+        game: usize,
+        depth: usize,
+        round: usize,
+    },
+    GameWon {
+        game: usize,
+        depth: usize,
+        winner: Player,
+    },
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("Round {round} of the main game plays {logged:?}, but the decks give {drawn:?}")]
+    Mismatch {
+        round: usize,
+        logged: [Card; 2],
+        drawn: [Option<Card>; 2],
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct GameLog {
+    decks: [Deck; 2],
+    events: Vec<Event>,
+}
+
+impl GameLog {
+    pub fn new(decks: [Deck; 2], events: Vec<Event>) -> Self {
+        GameLog { decks, events }
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn replay(&self) -> Result<[Deck; 2], ReplayError> {
+        let [mut deck1, mut deck2] = self.decks.clone();
+        let mut played = [0, 0];
+        for event in &self.events {
+            match event {
+                Event::CardsPlayed {
+                    depth: 0,
+                    round,
+                    cards,
+                    ..
+                } => {
+                    let drawn = [deck1.pop_front(), deck2.pop_front()];
+                    if drawn != cards.map(Some) {
+                        return Err(ReplayError::Mismatch {
+                            round: *round,
+                            logged: *cards,
+                            drawn,
+                        });
+                    }
+                    played = *cards;
+                }
+                Event::RoundWon {
+                    depth: 0, winner, ..
+                } => match winner {
+                    Player::One => deck1.extend([played[0], played[1]]),
+                    Player::Two => deck2.extend([played[1], played[0]]),
+                },
+                _ => {}
+            }
+        }
+        Ok([deck1, deck2])
+    }
+}
+
+impl Display for GameLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // games that are still going on, to know where to go back after a sub-game
+        let mut games: Vec<usize> = vec![];
+        let mut back_from_sub_game = false;
+
+        for event in &self.events {
+            match event {
+                Event::GameStarted { game, depth } => {
+                    if *depth > 0 {
+                        writeln!(f, "Playing a sub-game to determine the winner...\n")?;
+                    }
+                    writeln!(f, "=== Game {} ===\n", game)?;
+                    games.push(*game);
+                }
+                Event::CardsPlayed {
+                    game,
+                    round,
+                    decks,
+                    cards,
+                    ..
+                } => {
+                    writeln!(f, "-- Round {} (Game {}) --", round, game)?;
+                    writeln!(f, "Player 1's deck: {}", decks[0])?;
+                    writeln!(f, "Player 2's deck: {}", decks[1])?;
+                    writeln!(f, "Player 1 plays: {}", cards[0])?;
+                    writeln!(f, "Player 2 plays: {}", cards[1])?;
+                }
+                Event::SubGameRecalled { winner, .. } => {
+                    writeln!(
+                        f,
+                        "This sub-game was already played: player {} wins it.",
+                        winner
+                    )?;
+                }
+                Event::RoundWon {
+                    game,
+                    round,
+                    winner,
+                    ..
+                } => {
+                    if back_from_sub_game {
+                        writeln!(f, "...anyway, back to game {}.", game)?;
+                        back_from_sub_game = false;
+                    }
+                    writeln!(
+                        f,
+                        "Player {} wins round {} of game {}!\n",
+                        winner, round, game
+                    )?;
+                }
+                Event::StateRepeated { game, round, .. } => {
+                    writeln!(
+                        f,
+                        "-- Round {} (Game {}) --\nThese decks were already played in game {}.",
+                        round, game, game
+                    )?;
+                }
+                Event::GameWon { game, winner, .. } => {
+                    writeln!(f, "The winner of game {} is player {}!\n", game, winner)?;
+                    games.pop();
+                    back_from_sub_game = !games.is_empty();
+                }
+            }
+        }
+
+        writeln!(f, "\n== Post-game results ==")?;
+        match self.replay() {
+            Ok([deck1, deck2]) => {
+                writeln!(f, "Player 1's deck: {}", deck1)?;
+                write!(f, "Player 2's deck: {}", deck2)
+            }
+            Err(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RecursiveCombat;
+
+    use super::*;
+
+    fn example() -> (Deck, Deck) {
+        let input = include_str!("../example.txt");
+        let (p1, p2) = input.split_once("\n\n").unwrap();
+        (p1.parse().unwrap(), p2.parse().unwrap())
+    }
+
+    #[test]
+    fn test_narrative() {
+        let (deck1, deck2) = example();
+        let log = RecursiveCombat::new()
+            .without_memo()
+            .play_logged(deck1, deck2);
+        let story = log.to_string();
+
+        assert!(story.starts_with(
+            "=== Game 1 ===
+
+-- Round 1 (Game 1) --
+Player 1's deck: 9, 2, 6, 3, 1
+Player 2's deck: 5, 8, 4, 7, 10
+Player 1 plays: 9
+Player 2 plays: 5
+Player 1 wins round 1 of game 1!
+
+-- Round 2 (Game 1) --
+"
+        ));
+        assert!(story.contains(
+            "Player 1 plays: 4
+Player 2 plays: 3
+Playing a sub-game to determine the winner...
+
+=== Game 2 ===
+
+-- Round 1 (Game 2) --
+Player 1's deck: 9, 8, 5, 2
+Player 2's deck: 10, 1, 7
+"
+        ));
+        assert!(story.contains(
+            "The winner of game 2 is player 2!
+
+...anyway, back to game 1.
+Player 2 wins round 9 of game 1!
+"
+        ));
+        assert!(story.ends_with(
+            "The winner of game 1 is player 2!
+
+
+== Post-game results ==
+Player 1's deck: \nPlayer 2's deck: 7, 5, 6, 2, 4, 1, 10, 8, 9, 3"
+        ));
+    }
+
+    #[test]
+    fn test_replay() {
+        let (deck1, deck2) = example();
+        let log = RecursiveCombat::new().play_logged(deck1, deck2);
+        let [deck1, deck2] = log.replay().unwrap();
+        assert!(deck1.is_empty());
+        assert_eq!(deck2.calculate_score(), 291);
+
+        let rounds = log
+            .events()
+            .iter()
+            .filter(|event| matches!(event, Event::RoundWon { depth: 0, .. }))
+            .count();
+        assert_eq!(rounds, 17);
+        assert!(log.events().iter().all(|event| !matches!(
+            event,
+            Event::GameWon {
+                depth: 0,
+                winner: Player::One,
+                ..
+            }
+        )));
+
+        let (deck1, deck2) = example();
+        let swapped = GameLog::new([deck2, deck1], log.events().to_vec());
+        let err = swapped.replay().unwrap_err();
+        assert_eq!(
+            err,
+            ReplayError::Mismatch {
+                round: 1,
+                logged: [9, 5],
+                drawn: [Some(5), Some(9)]
+            }
+        );
+        assert!(swapped.to_string().ends_with(&err.to_string()));
+    }
+
+    #[test]
+    fn test_infinite_game() {
+        let deck1: Deck = "Player 1:\n43\n19".parse().unwrap();
+        let deck2: Deck = "Player 2:\n2\n29\n14".parse().unwrap();
+        let log = RecursiveCombat::new().play_logged(deck1, deck2);
+        assert!(matches!(
+            log.events().last(),
+            Some(Event::GameWon {
+                game: 1,
+                winner: Player::One,
+                ..
+            })
+        ));
+        assert!(log
+            .to_string()
+            .contains("These decks were already played in game 1."));
+    }
+}
@@ -1,45 +1,61 @@
-use itertools::Itertools;
-
-#[derive(Debug)]
+// `data[label]` is the label right after `label` (`data[0]` is unused)
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CLList {
-    data: Box<[usize]>,
+    data: Box<[u32]>,
 }
 
 impl CLList {
-    pub fn from_iter(iter: impl Iterator<Item = usize> + Clone, first: usize) -> Self {
-        let mut data = vec![0; 1_000_001];
+    pub fn new(head: &[u32], len: u32) -> Self {
+        let mut data: Vec<u32> = (1..=len + 1).collect();
+        data[0] = 0;
+
+        for pair in head.windows(2) {
+            data[pair[0] as usize] = pair[1];
+        }
 
-        let mut last = 0;
-        for (a, b) in iter.into_iter().tuple_windows() {
-            data[a] = b;
-            last = b;
+        let first = head[0];
+        let last_of_head = *head.last().unwrap();
+        if len as usize > head.len() {
+            data[last_of_head as usize] = head.len() as u32 + 1;
+            data[len as usize] = first;
+        } else {
+            data[last_of_head as usize] = first;
         }
-        data[last] = first;
 
         CLList {
             data: data.into_boxed_slice(),
         }
     }
 
-    pub fn next(&self, i: usize) -> usize {
-        self.data[i]
+    pub fn from_successors(data: Vec<u32>) -> Self {
+        CLList {
+            data: data.into_boxed_slice(),
+        }
     }
 
-    pub fn pop3_after(&mut self, i: usize) -> [usize; 3] {
-        let one = self.next(i);
-        let two = self.next(one);
-        let three = self.next(two);
+    pub fn successors(&self) -> &[u32] {
+        &self.data
+    }
 
-        self.data[i] = self.next(three);
+    pub fn next(&self, i: u32) -> u32 {
+        self.data[i as usize]
+    }
 
-        [one, two, three]
+    pub fn pop_after(&mut self, i: u32, picked: &mut [u32]) {
+        let mut curr = i;
+        for el in picked.iter_mut() {
+            curr = self.next(curr);
+            *el = curr;
+        }
+
+        self.data[i as usize] = self.next(curr);
     }
 
-    pub fn push3_after(&mut self, i: usize, elements: [usize; 3]) {
+    pub fn push_after(&mut self, i: u32, picked: &[u32]) {
         let next_target = self.next(i);
 
-        self.data[i] = elements[0];
-        self.data[elements[2]] = next_target;
+        self.data[i as usize] = picked[0];
+        self.data[picked[picked.len() - 1] as usize] = next_target;
     }
 }
 
@@ -49,16 +65,41 @@ mod tests {
 
     #[test]
     fn test_constructor() {
-        let data: [usize; 6] = [1, 2, 3, 4, 5, 6];
-        let mut list: CLList = CLList::from_iter(data.into_iter(), 1);
+        let mut list = CLList::new(&[1, 2, 3, 4, 5, 6], 6);
         assert_eq!(list.next(3), 4);
         assert_eq!(list.next(6), 1);
 
-        assert_eq!(list.pop3_after(2), [3, 4, 5]);
+        let mut picked = [0; 3];
+        list.pop_after(2, &mut picked);
+        assert_eq!(picked, [3, 4, 5]);
         assert_eq!(list.next(2), 6);
 
-        list.push3_after(1, [3, 4, 5]);
+        list.push_after(1, &[3, 4, 5]);
         assert_eq!(list.next(1), 3);
         assert_eq!(list.next(5), 2);
     }
+
+    #[test]
+    fn test_constructor_with_tail() {
+        let list = CLList::new(&[3, 1, 2], 5);
+        assert_eq!(list.next(3), 1);
+        assert_eq!(list.next(1), 2);
+        assert_eq!(list.next(2), 4);
+        assert_eq!(list.next(4), 5);
+        assert_eq!(list.next(5), 3);
+    }
+
+    #[test]
+    fn test_pick_up_sizes() {
+        let mut list = CLList::new(&[1, 2, 3, 4, 5, 6], 6);
+        let mut picked = [0; 1];
+        list.pop_after(6, &mut picked);
+        assert_eq!(picked, [1]);
+        assert_eq!(list.next(6), 2);
+
+        let mut picked = [0; 4];
+        list.pop_after(2, &mut picked);
+        assert_eq!(picked, [3, 4, 5, 6]);
+        assert_eq!(list.next(2), 2);
+    }
 }
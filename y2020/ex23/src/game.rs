@@ -0,0 +1,443 @@
+use crate::cllist::CLList;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+const CHECKPOINT_MAGIC: &[u8; 4] = b"CUP1";
+
+#[derive(Error, Debug)]
+pub enum GameError {
+    #[error("Invalid cup label '{0}'")]
+    InvalidLabel(String),
+    #[error("The starting labels must be a permutation of 1..={0}")]
+    NotAPermutation(usize),
+    #[error("There are {labels} starting labels but only {cups} cups")]
+    TooFewCups { labels: usize, cups: u32 },
+    #[error("There can be at most {} cups", u32::MAX - 1)]
+    TooManyCups,
+    #[error("Cannot pick up {pick_up} cups out of {cups}: a destination needs to be left")]
+    PickUpTooLarge { pick_up: usize, cups: u32 },
+    #[error("{cups} cups need {needed} bytes, more than the limit of {limit} bytes")]
+    MemoryLimitExceeded {
+        cups: u32,
+        needed: usize,
+        limit: usize,
+    },
+    #[error("Invalid checkpoint: {0}")]
+    InvalidCheckpoint(&'static str),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Destination {
+    LowerLabel,
+    HigherLabel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameConfig {
+    pub pick_up: usize,
+    pub cups: u32,
+    pub destination: Destination,
+    pub memory_limit: Option<usize>,
+}
+
+impl GameConfig {
+    pub fn new(cups: u32) -> Self {
+        GameConfig {
+            pick_up: 3,
+            cups,
+            destination: Destination::LowerLabel,
+            memory_limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub picked: Vec<u32>,
+    pub destination: u32,
+}
+
+pub fn parse_labels(input: &str) -> Result<Vec<u32>, GameError> {
+    let input = input.trim();
+    if input.contains(|c: char| c == ',' || c.is_whitespace()) {
+        input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| GameError::InvalidLabel(s.to_string()))
+            })
+            .collect()
+    } else {
+        input
+            .chars()
+            .map(|c| {
+                c.to_digit(10)
+                    .ok_or_else(|| GameError::InvalidLabel(c.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CupGame {
+    config: GameConfig,
+    cups: CLList,
+    current: u32,
+    moves: u64,
+    picked: Vec<u32>,
+}
+
+impl CupGame {
+    pub fn new(labels: &[u32], config: GameConfig) -> Result<Self, GameError> {
+        let mut seen = vec![false; labels.len() + 1];
+        for label in labels {
+            match seen.get_mut(*label as usize) {
+                Some(seen) if *label > 0 && !*seen => *seen = true,
+                _ => return Err(GameError::NotAPermutation(labels.len())),
+            }
+        }
+        if labels.is_empty() {
+            return Err(GameError::NotAPermutation(0));
+        }
+
+        Self::check_config(&config, labels.len())?;
+
+        Ok(CupGame {
+            cups: CLList::new(labels, config.cups),
+            current: labels[0],
+            moves: 0,
+            picked: vec![0; config.pick_up],
+            config,
+        })
+    }
+
+    fn check_config(config: &GameConfig, labels: usize) -> Result<(), GameError> {
+        if config.cups == u32::MAX {
+            return Err(GameError::TooManyCups);
+        }
+        if (config.cups as usize) < labels {
+            return Err(GameError::TooFewCups {
+                labels,
+                cups: config.cups,
+            });
+        }
+        if config.pick_up == 0 || config.pick_up + 2 > config.cups as usize {
+            return Err(GameError::PickUpTooLarge {
+                pick_up: config.pick_up,
+                cups: config.cups,
+            });
+        }
+        if let Some(limit) = config.memory_limit {
+            let needed = Self::memory_needed(config.cups);
+            if needed > limit {
+                return Err(GameError::MemoryLimitExceeded {
+                    cups: config.cups,
+                    needed,
+                    limit,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn memory_needed(cups: u32) -> usize {
+        (cups as usize + 1) * std::mem::size_of::<u32>()
+    }
+
+    pub fn moves(&self) -> u64 {
+        self.moves
+    }
+
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    pub fn next_cup(&self, label: u32) -> u32 {
+        self.cups.next(label)
+    }
+
+    pub fn labels_after_one(&self) -> impl Iterator<Item = u32> + '_ {
+        let mut curr = 1;
+        std::iter::from_fn(move || {
+            curr = self.cups.next(curr);
+            (curr != 1).then_some(curr)
+        })
+    }
+
+    fn play(&mut self) -> u32 {
+        let cups = self.config.cups;
+        self.cups.pop_after(self.current, &mut self.picked);
+
+        let mut target = self.current;
+        loop {
+            target = match self.config.destination {
+                Destination::LowerLabel if target == 1 => cups,
+                Destination::LowerLabel => target - 1,
+                Destination::HigherLabel if target == cups => 1,
+                Destination::HigherLabel => target + 1,
+            };
+            if !self.picked.contains(&target) {
+                break;
+            }
+        }
+
+        self.cups.push_after(target, &self.picked);
+        self.current = self.cups.next(self.current);
+        self.moves += 1;
+        target
+    }
+
+    pub fn run(&mut self, moves: u64) {
+        for _ in 0..moves {
+            self.play();
+        }
+    }
+
+    pub fn checkpoint(&self, mut writer: impl Write) -> Result<(), GameError> {
+        writer.write_all(CHECKPOINT_MAGIC)?;
+        writer.write_all(&(self.config.pick_up as u32).to_le_bytes())?;
+        writer.write_all(&self.config.cups.to_le_bytes())?;
+        writer.write_all(&[match self.config.destination {
+            Destination::LowerLabel => 0,
+            Destination::HigherLabel => 1,
+        }])?;
+        writer.write_all(&self.current.to_le_bytes())?;
+        writer.write_all(&self.moves.to_le_bytes())?;
+        for successor in self.cups.successors() {
+            writer.write_all(&successor.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn resume(mut reader: impl Read, memory_limit: Option<usize>) -> Result<Self, GameError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(GameError::InvalidCheckpoint("unknown format"));
+        }
+
+        let pick_up = read_u32(&mut reader)? as usize;
+        let cups = read_u32(&mut reader)?;
+        let mut destination = [0; 1];
+        reader.read_exact(&mut destination)?;
+        let destination = match destination[0] {
+            0 => Destination::LowerLabel,
+            1 => Destination::HigherLabel,
+            _ => return Err(GameError::InvalidCheckpoint("unknown destination rule")),
+        };
+        let config = GameConfig {
+            pick_up,
+            cups,
+            destination,
+            memory_limit,
+        };
+        Self::check_config(&config, 0)?;
+
+        let current = read_u32(&mut reader)?;
+        let mut moves = [0; 8];
+        reader.read_exact(&mut moves)?;
+        let moves = u64::from_le_bytes(moves);
+
+        // `cups` is not trusted yet: grow with what is actually read
+        let mut successors = vec![];
+        for _ in 0..=cups {
+            let successor = read_u32(&mut reader)?;
+            if successor > cups {
+                return Err(GameError::InvalidCheckpoint("successor out of range"));
+            }
+            successors.push(successor);
+        }
+        // following the successors from cup 1 has to go through every cup exactly once
+        let mut seen = vec![false; cups as usize + 1];
+        let mut cup = 1;
+        for _ in 0..cups {
+            cup = successors[cup as usize];
+            if cup == 0 || seen[cup as usize] {
+                return Err(GameError::InvalidCheckpoint(
+                    "successors are not a single cycle",
+                ));
+            }
+            seen[cup as usize] = true;
+        }
+        if cup != 1 || successors[0] != 0 {
+            return Err(GameError::InvalidCheckpoint(
+                "successors are not a single cycle",
+            ));
+        }
+        if current == 0 || current > cups {
+            return Err(GameError::InvalidCheckpoint("current cup out of range"));
+        }
+
+        Ok(CupGame {
+            cups: CLList::from_successors(successors),
+            current,
+            moves,
+            picked: vec![0; pick_up],
+            config,
+        })
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, GameError> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+impl Iterator for CupGame {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let destination = self.play();
+        Some(Move {
+            picked: self.picked.clone(),
+            destination,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels_after_one(game: &CupGame) -> String {
+        game.labels_after_one().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_labels() {
+        assert_eq!(
+            parse_labels("389125467\n").unwrap(),
+            [3, 8, 9, 1, 2, 5, 4, 6, 7]
+        );
+        assert_eq!(parse_labels("3, 10, 1 2").unwrap(), [3, 10, 1, 2]);
+        assert!(matches!(
+            parse_labels("38a"),
+            Err(GameError::InvalidLabel(label)) if label == "a"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        assert!(matches!(
+            CupGame::new(&[1, 2, 2], GameConfig::new(3)),
+            Err(GameError::NotAPermutation(3))
+        ));
+        assert!(matches!(
+            CupGame::new(&[1, 2, 4], GameConfig::new(4)),
+            Err(GameError::NotAPermutation(3))
+        ));
+        assert!(matches!(
+            CupGame::new(&[1, 2, 3], GameConfig::new(2)),
+            Err(GameError::TooFewCups { labels: 3, cups: 2 })
+        ));
+        assert!(matches!(
+            CupGame::new(&[1, 2, 3], GameConfig::new(u32::MAX)),
+            Err(GameError::TooManyCups)
+        ));
+        assert!(matches!(
+            CupGame::new(&[1, 2, 3, 4], GameConfig::new(4)),
+            Err(GameError::PickUpTooLarge {
+                pick_up: 3,
+                cups: 4
+            })
+        ));
+
+        let config = GameConfig {
+            memory_limit: Some(1_000),
+            ..GameConfig::new(1_000_000)
+        };
+        assert!(matches!(
+            CupGame::new(&[1, 2, 3], config),
+            Err(GameError::MemoryLimitExceeded {
+                needed: 4_000_004,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_pick_up_size_and_destination() {
+        let config = GameConfig {
+            pick_up: 2,
+            ..GameConfig::new(5)
+        };
+        let mut game = CupGame::new(&[3, 1, 2, 5, 4], config).unwrap();
+        // (3) 1 2 5 4 -> picks 1 2, destination 5 -> 3 (5) 1 2 4
+        let first = game.next().unwrap();
+        assert_eq!(first.picked, [1, 2]);
+        assert_eq!(first.destination, 5);
+        assert_eq!(labels_after_one(&game), "2435");
+
+        let config = GameConfig {
+            destination: Destination::HigherLabel,
+            ..GameConfig::new(5)
+        };
+        let mut game = CupGame::new(&[3, 1, 2, 5, 4], config).unwrap();
+        // (3) 1 2 5 4 -> picks 1 2 5, destination 4 -> 3 (4) 1 2 5
+        let first = game.next().unwrap();
+        assert_eq!(first.picked, [1, 2, 5]);
+        assert_eq!(first.destination, 4);
+        assert_eq!(labels_after_one(&game), "2534");
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let labels = parse_labels("389125467").unwrap();
+        let config = GameConfig::new(1_000);
+        let mut straight = CupGame::new(&labels, config.clone()).unwrap();
+        straight.run(10_000);
+
+        let mut split = CupGame::new(&labels, config).unwrap();
+        split.run(4_000);
+        let mut saved = vec![];
+        split.checkpoint(&mut saved).unwrap();
+        assert_eq!(saved.len(), 4 + 4 + 4 + 1 + 4 + 8 + 1_001 * 4);
+
+        let mut resumed = CupGame::resume(saved.as_slice(), None).unwrap();
+        assert_eq!(resumed.moves(), 4_000);
+        assert_eq!(resumed.current(), split.current());
+        resumed.run(6_000);
+        assert_eq!(resumed.moves(), 10_000);
+        assert_eq!(resumed.current(), straight.current());
+        assert!(resumed.labels_after_one().eq(straight.labels_after_one()));
+
+        assert!(matches!(
+            CupGame::resume(&b"nope"[..], None),
+            Err(GameError::InvalidCheckpoint(_))
+        ));
+        assert!(matches!(
+            CupGame::resume(&saved[..20], None),
+            Err(GameError::Io(_))
+        ));
+        assert!(matches!(
+            CupGame::resume(saved.as_slice(), Some(100)),
+            Err(GameError::MemoryLimitExceeded { .. })
+        ));
+
+        // a huge number of cups fails on the missing successors, not on allocating them
+        let mut huge = saved.clone();
+        huge[8..12].copy_from_slice(&(u32::MAX - 1).to_le_bytes());
+        assert!(matches!(
+            CupGame::resume(huge.as_slice(), None),
+            Err(GameError::Io(_))
+        ));
+
+        // the successors of cups 1 and 2 start at byte 29
+        let successor = |label: usize| 25 + label * 4;
+        for (label, value) in [(1, 0), (1, 1), (2, split.next_cup(1))] {
+            let mut corrupted = saved.clone();
+            corrupted[successor(label)..successor(label) + 4].copy_from_slice(&value.to_le_bytes());
+            assert!(matches!(
+                CupGame::resume(corrupted.as_slice(), None),
+                Err(GameError::InvalidCheckpoint(
+                    "successors are not a single cycle"
+                ))
+            ));
+        }
+    }
+}
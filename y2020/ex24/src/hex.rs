@@ -0,0 +1,355 @@
+use std::{ops::Add, str::Chars};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::West,
+        Direction::NorthEast,
+        Direction::NorthWest,
+        Direction::SouthEast,
+        Direction::SouthWest,
+    ];
+
+    pub fn delta(&self) -> Axial {
+        match self {
+            Direction::East => Axial::new(1, 0),
+            Direction::West => Axial::new(-1, 0),
+            Direction::NorthEast => Axial::new(1, -1),
+            Direction::NorthWest => Axial::new(0, -1),
+            Direction::SouthEast => Axial::new(0, 1),
+            Direction::SouthWest => Axial::new(-1, 1),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseDirectionError {
+    #[error("Unexpected '{found}' at position {position}")]
+    UnexpectedChar { position: usize, found: char },
+    #[error("Unexpected end of input at position {position}")]
+    UnexpectedEnd { position: usize },
+}
+
+pub struct DirectionParser<'a> {
+    chars: Chars<'a>,
+    position: usize,
+}
+
+impl<'a> DirectionParser<'a> {
+    pub fn new(s: &'a str) -> Self {
+        DirectionParser {
+            chars: s.chars(),
+            position: 0,
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        self.position += 1;
+        self.chars.next()
+    }
+
+    fn error(&mut self, found: Option<char>) -> ParseDirectionError {
+        // stop at the first error
+        self.chars = "".chars();
+        let position = self.position - 1;
+        match found {
+            Some(found) => ParseDirectionError::UnexpectedChar { position, found },
+            None => ParseDirectionError::UnexpectedEnd { position },
+        }
+    }
+}
+
+impl Iterator for DirectionParser<'_> {
+    type Item = Result<Direction, ParseDirectionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let direction = match self.next_char()? {
+            'e' => Direction::East,
+            'w' => Direction::West,
+            'n' => match self.next_char() {
+                Some('e') => Direction::NorthEast,
+                Some('w') => Direction::NorthWest,
+                c => return Some(Err(self.error(c))),
+            },
+            's' => match self.next_char() {
+                Some('e') => Direction::SouthEast,
+                Some('w') => Direction::SouthWest,
+                c => return Some(Err(self.error(c))),
+            },
+            c => return Some(Err(self.error(Some(c)))),
+        };
+        Some(Ok(direction))
+    }
+}
+
+pub fn parse_directions(s: &str) -> Result<Vec<Direction>, ParseDirectionError> {
+    DirectionParser::new(s).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Axial {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cube {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Doubled {
+    pub col: i32,
+    pub row: i32,
+}
+
+impl From<Axial> for Cube {
+    fn from(a: Axial) -> Self {
+        Cube {
+            q: a.q,
+            r: a.r,
+            s: -a.q - a.r,
+        }
+    }
+}
+
+impl From<Cube> for Axial {
+    fn from(c: Cube) -> Self {
+        Axial::new(c.q, c.r)
+    }
+}
+
+impl From<Axial> for Doubled {
+    fn from(a: Axial) -> Self {
+        Doubled {
+            col: 2 * a.q + a.r,
+            row: a.r,
+        }
+    }
+}
+
+impl From<Doubled> for Axial {
+    fn from(d: Doubled) -> Self {
+        Axial::new((d.col - d.row).div_euclid(2), d.row)
+    }
+}
+
+impl Add for Axial {
+    type Output = Axial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Axial::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Axial {
+    pub const ORIGIN: Axial = Axial { q: 0, r: 0 };
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Axial { q, r }
+    }
+
+    pub fn walk(self, path: impl IntoIterator<Item = Direction>) -> Self {
+        path.into_iter().fold(self, |tile, dir| tile.neighbour(dir))
+    }
+
+    pub fn scale(self, k: i32) -> Self {
+        Axial::new(self.q * k, self.r * k)
+    }
+
+    pub fn neighbour(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+
+    pub fn neighbours(self) -> [Axial; 6] {
+        Direction::ALL.map(|direction| self.neighbour(direction))
+    }
+
+    pub fn distance(self, other: Axial) -> u32 {
+        let dq = self.q - other.q;
+        let dr = self.r - other.r;
+        (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs()) / 2
+    }
+
+    pub fn ring(self, radius: u32) -> Vec<Axial> {
+        if radius == 0 {
+            return vec![self];
+        }
+
+        let sides = [
+            Direction::NorthEast,
+            Direction::East,
+            Direction::SouthEast,
+            Direction::SouthWest,
+            Direction::West,
+            Direction::NorthWest,
+        ];
+        let mut tile = self + Direction::West.delta().scale(radius as i32);
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for direction in sides {
+            for _ in 0..radius {
+                ring.push(tile);
+                tile = tile.neighbour(direction);
+            }
+        }
+        ring
+    }
+
+    pub fn spiral(self, radius: u32) -> Vec<Axial> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    pub fn line_to(self, other: Axial) -> Vec<Axial> {
+        let distance = self.distance(other);
+        if distance == 0 {
+            return vec![self];
+        }
+
+        // nudge the end points so that lines exactly on an edge always fall on the same side
+        let (aq, ar) = (self.q as f64 + 1e-6, self.r as f64 + 1e-6);
+        let (bq, br) = (other.q as f64 + 1e-6, other.r as f64 + 1e-6);
+        (0..=distance)
+            .map(|i| {
+                let t = i as f64 / distance as f64;
+                cube_round(aq + (bq - aq) * t, ar + (br - ar) * t)
+            })
+            .collect()
+    }
+}
+
+fn cube_round(q: f64, r: f64) -> Axial {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    Axial::new(rq as i32, rr as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directions() {
+        use Direction::*;
+        assert_eq!(
+            parse_directions("nwwswee").unwrap(),
+            [NorthWest, West, SouthWest, East, East]
+        );
+        assert_eq!(parse_directions("").unwrap(), []);
+        assert_eq!(
+            parse_directions("ewnx"),
+            Err(ParseDirectionError::UnexpectedChar {
+                position: 3,
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            parse_directions("es"),
+            Err(ParseDirectionError::UnexpectedEnd { position: 2 })
+        );
+        assert_eq!(
+            parse_directions("q").unwrap_err().to_string(),
+            "Unexpected 'q' at position 0"
+        );
+    }
+
+    #[test]
+    fn test_walk() {
+        let path = parse_directions("nwwswee").unwrap();
+        assert_eq!(Axial::ORIGIN.walk(path), Axial::ORIGIN);
+
+        let path = parse_directions("esew").unwrap();
+        assert_eq!(Axial::ORIGIN.walk(path), Axial::new(0, 1));
+    }
+
+    #[test]
+    fn test_conversions() {
+        for tile in Axial::ORIGIN.spiral(3) {
+            let cube: Cube = tile.into();
+            assert_eq!(cube.q + cube.r + cube.s, 0);
+            assert_eq!(Axial::from(cube), tile);
+
+            let doubled: Doubled = tile.into();
+            assert_eq!((doubled.col + doubled.row) % 2, 0);
+            assert_eq!(Axial::from(doubled), tile);
+        }
+
+        // east and west move by 2 columns in doubled coordinates
+        let east: Doubled = Axial::ORIGIN.neighbour(Direction::East).into();
+        assert_eq!(east, Doubled { col: 2, row: 0 });
+        let north_west: Doubled = Axial::ORIGIN.neighbour(Direction::NorthWest).into();
+        assert_eq!(north_west, Doubled { col: -1, row: -1 });
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Axial::new(1, -3);
+        assert_eq!(a.distance(a), 0);
+        for n in a.neighbours() {
+            assert_eq!(a.distance(n), 1);
+        }
+        assert_eq!(Axial::new(-2, 0).distance(Axial::new(2, -1)), 4);
+        assert_eq!(Axial::new(0, 3).distance(Axial::new(3, 0)), 3);
+    }
+
+    #[test]
+    fn test_rings_and_spirals() {
+        let center = Axial::new(2, -1);
+        assert_eq!(center.ring(0), [center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|tile| tile.distance(center) == radius));
+            // consecutive tiles of a ring are adjacent, and the ring is closed
+            for (i, tile) in ring.iter().enumerate() {
+                assert_eq!(tile.distance(ring[(i + 1) % ring.len()]), 1);
+            }
+        }
+
+        let spiral = center.spiral(3);
+        assert_eq!(spiral.len(), 1 + 6 + 12 + 18);
+        let mut unique = spiral.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), spiral.len());
+    }
+
+    #[test]
+    fn test_line() {
+        let a = Axial::new(0, 0);
+        assert_eq!(a.line_to(a), [a]);
+
+        let b = Axial::new(3, -3);
+        assert_eq!(
+            a.line_to(b),
+            [a, Axial::new(1, -1), Axial::new(2, -2), Axial::new(3, -3)]
+        );
+
+        let c = Axial::new(-4, 1);
+        let line = c.line_to(Axial::new(2, 3));
+        assert_eq!(line.len() as u32, c.distance(Axial::new(2, 3)) + 1);
+        for pair in line.windows(2) {
+            assert_eq!(pair[0].distance(pair[1]), 1);
+        }
+    }
+}
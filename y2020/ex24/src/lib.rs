@@ -1,79 +1,92 @@
-use std::{
-    collections::HashSet,
-    str::{Chars, FromStr},
-};
-
-const DELTAS: [(i32, i32); 6] = [(2, 0), (-2, 0), (1, 1), (-1, -1), (-1, 1), (1, -1)];
+mod hex;
 
-#[derive(Hash, PartialEq, Eq, Clone, Default)]
-struct Tile {
-    x: i32,
-    y: i32,
+pub use hex::{
+    parse_directions, Axial, Cube, Direction, DirectionParser, Doubled, ParseDirectionError,
+};
+use std::{collections::HashSet, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    #[error("Rule must have the form B<counts>/S<counts>, found '{0}'")]
+    InvalidFormat(String),
+    #[error("Invalid neighbour count '{0}': a tile only has 6 neighbours")]
+    InvalidCount(char),
+    #[error("B0 rules are not supported: they would flip the whole infinite floor")]
+    BirthOnZero,
 }
 
-struct CommandParser<'a> {
-    chars: Chars<'a>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: [bool; 7],
+    survival: [bool; 7],
 }
 
-impl<'a> CommandParser<'a> {
-    fn new(chars: Chars<'a>) -> Self {
-        CommandParser { chars }
+impl Default for Rule {
+    fn default() -> Self {
+        "B2/S12".parse().unwrap()
     }
 }
 
-impl Iterator for CommandParser<'_> {
-    type Item = (i32, i32);
+impl FromStr for Rule {
+    type Err = RuleParseError;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.chars.next() {
-            Some('e') => Some((2, 0)),
-            Some('w') => Some((-2, 0)),
-            Some('n') => match self.chars.next() {
-                Some('e') => Some((1, -1)),
-                Some('w') => Some((-1, -1)),
-                _ => None,
-            },
-            Some('s') => match self.chars.next() {
-                Some('e') => Some((1, 1)),
-                Some('w') => Some((-1, 1)),
-                _ => None,
-            },
-            _ => None,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RuleParseError::InvalidFormat(s.to_string());
+        let (birth, survival) = s.split_once('/').ok_or_else(invalid)?;
+        let birth = birth.strip_prefix('B').ok_or_else(invalid)?;
+        let survival = survival.strip_prefix('S').ok_or_else(invalid)?;
+
+        let counts = |s: &str| {
+            let mut flags = [false; 7];
+            for c in s.chars() {
+                match c.to_digit(10) {
+                    Some(n) if n <= 6 => flags[n as usize] = true,
+                    _ => return Err(RuleParseError::InvalidCount(c)),
+                }
+            }
+            Ok(flags)
+        };
+
+        let birth = counts(birth)?;
+        if birth[0] {
+            return Err(RuleParseError::BirthOnZero);
         }
+
+        Ok(Rule {
+            birth,
+            survival: counts(survival)?,
+        })
     }
 }
 
-impl FromStr for Tile {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = CommandParser::new(s.chars())
-            .fold((0, 0), |acc, curr| (acc.0 + curr.0, acc.1 + curr.1));
-        Ok(Tile { x, y })
-    }
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Invalid path on line {line}: {source}")]
+pub struct LobbyParseError {
+    line: usize,
+    source: ParseDirectionError,
 }
 
-impl Tile {
-    fn neighbours(&self) -> [Tile; 6] {
-        let mut ret: [Tile; 6] = Default::default();
+#[derive(Debug, Clone)]
+pub struct Lobby {
+    tiles: HashSet<Axial>,
+    rule: Rule,
+}
 
-        for (i, (dx, dy)) in DELTAS.iter().enumerate() {
-            ret[i] = Tile {
-                x: self.x + dx,
-                y: self.y + dy,
-            };
-        }
+impl Lobby {
+    pub fn with_rule(self, rule: Rule) -> Self {
+        Lobby { rule, ..self }
+    }
 
-        ret
+    pub fn black_tiles(&self) -> usize {
+        self.tiles.len()
     }
-}
 
-struct Lobby {
-    tiles: HashSet<Tile>,
-}
+    pub fn is_black(&self, tile: Axial) -> bool {
+        self.tiles.contains(&tile)
+    }
 
-impl Lobby {
-    fn neighbours(&self, tile: &Tile) -> usize {
+    fn neighbours(&self, tile: Axial) -> usize {
         tile.neighbours()
             .into_iter()
             .filter(|tile| self.tiles.contains(tile))
@@ -82,19 +95,24 @@ impl Lobby {
 }
 
 impl FromStr for Lobby {
-    type Err = ();
+    type Err = LobbyParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut tiles: HashSet<Tile> = Default::default();
-        for line in s.lines() {
-            let tile: Tile = line.parse().unwrap();
-            if tiles.contains(&tile) {
-                tiles.remove(&tile);
-            } else {
+        let mut tiles: HashSet<Axial> = Default::default();
+        for (line, path) in s.lines().enumerate() {
+            let path = parse_directions(path).map_err(|source| LobbyParseError {
+                line: line + 1,
+                source,
+            })?;
+            let tile = Axial::ORIGIN.walk(path);
+            if !tiles.remove(&tile) {
                 tiles.insert(tile);
             }
         }
-        Ok(Lobby { tiles })
+        Ok(Lobby {
+            tiles,
+            rule: Rule::default(),
+        })
     }
 }
 
@@ -102,21 +120,22 @@ impl Iterator for Lobby {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut new_tiles: HashSet<Tile> = Default::default();
-
         let mut to_check = self.tiles.clone();
         for tile in &self.tiles {
-            for n_tile in tile.neighbours() {
-                to_check.insert(n_tile);
-            }
+            to_check.extend(tile.neighbours());
         }
 
-        for tile in to_check {
-            let n_neighbours = self.neighbours(&tile);
-            if n_neighbours == 2 || (n_neighbours == 1 && self.tiles.contains(&tile)) {
-                new_tiles.insert(tile);
-            }
-        }
+        let new_tiles = to_check
+            .into_iter()
+            .filter(|tile| {
+                let n_neighbours = self.neighbours(*tile);
+                if self.tiles.contains(tile) {
+                    self.rule.survival[n_neighbours]
+                } else {
+                    self.rule.birth[n_neighbours]
+                }
+            })
+            .collect();
 
         self.tiles = new_tiles;
         Some(self.tiles.len())
@@ -124,7 +143,7 @@ impl Iterator for Lobby {
 }
 
 pub fn part1(input: &str) -> usize {
-    input.parse::<Lobby>().unwrap().tiles.len()
+    input.parse::<Lobby>().unwrap().black_tiles()
 }
 
 pub fn part2(input: &str) -> usize {
@@ -135,6 +154,65 @@ pub fn part2(input: &str) -> usize {
 mod ex24_tests {
     use super::*;
 
+    const EXAMPLE: &str = "sesenwnenenewseeswwswswwnenewsewsw
+neeenesenwnwwswnenewnwwsewnenwseswesw
+seswneswswsenwwnwse
+nwnwneseeswswnenewneswwnewseswneseene
+swweswneswnenwsewnwneneseenw
+eesenwseswswnenwswnwnwsewwnwsene
+sewnenenenesenwsewnenwwwse
+wenwwweseeeweswwwnwwe
+wsweesenenewnwwnwsenewsenwwsesesenwne
+neeswseenwwswnwswswnw
+nenwswwsewswnenenewsenwsenwnesesenew
+enewnwewneswsewnwswenweswnenwsenwsw
+sweneswneswneneenwnewenewwneswswnese
+swwesenesewenwneswnwwneseswwne
+enesenwswwswneneswsenwnewswseenwsese
+wnwnesenesenenwwnenwsewesewsesesew
+nenewswnwewswnenesenwnesewesw
+eneswnwswnwsenenwnwnwwseeswneewsenese
+neswnwewnwnwseenwseesewsenwsweewe
+wseweeenwnesenwwwswnew";
+
+    #[test]
+    fn test_example() {
+        let mut lobby: Lobby = EXAMPLE.parse().unwrap();
+        assert_eq!(lobby.black_tiles(), 10);
+        assert_eq!(lobby.next(), Some(15));
+        assert_eq!(lobby.nth(98), Some(2208));
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        assert_eq!(
+            "B7/S".parse::<Rule>(),
+            Err(RuleParseError::InvalidCount('7'))
+        );
+        assert_eq!(
+            "S1/B2".parse::<Rule>(),
+            Err(RuleParseError::InvalidFormat("S1/B2".to_string()))
+        );
+        assert_eq!("B02/S12".parse::<Rule>(), Err(RuleParseError::BirthOnZero));
+
+        // without survival only the newborn tiles are left at every day:
+        // the tile between the two black ones is the only one with 2 black neighbours
+        let lobby: Lobby = "e\nw".parse().unwrap();
+        let mut lobby = lobby.with_rule("B2/S".parse().unwrap());
+        assert_eq!(lobby.next(), Some(1));
+        assert!(lobby.is_black(Axial::ORIGIN));
+        assert!(!lobby.is_black(Axial::new(1, 0)));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let err = "esew\nnwx".parse::<Lobby>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid path on line 2: Unexpected 'x' at position 2"
+        );
+    }
+
     #[test]
     fn part_1() {
         let input = include_str!("../input.txt");
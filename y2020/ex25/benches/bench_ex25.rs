@@ -1,9 +1,18 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use ex25::part1;
+use ex25::{crack, part1, Algorithm};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let input = include_str!("../input.txt");
     c.bench_function("ex25::part1", |b| b.iter(|| part1(black_box(input))));
+    for algorithm in [
+        Algorithm::BruteForce,
+        Algorithm::BabyStepGiantStep,
+        Algorithm::PohligHellman,
+    ] {
+        c.bench_function(&format!("ex25::{:?}", algorithm), |b| {
+            b.iter(|| crack(black_box(input), Some(algorithm)))
+        });
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
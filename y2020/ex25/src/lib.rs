@@ -1,60 +1,155 @@
-const MAGIC_NUMBER: usize = 20201227;
-const DEFAULT_SUBJECT_NUMBER: usize = 7;
-
-fn calculate_loop_size(n: usize) -> usize {
-    let mut loop_size = 0;
-    let mut acc = 1;
-    loop {
-        if acc == n {
-            return loop_size;
-        }
+mod modarith;
 
-        acc *= DEFAULT_SUBJECT_NUMBER;
-        acc %= MAGIC_NUMBER;
+pub use modarith::{
+    discrete_log, factorize, is_prime, order, pow_mod, Algorithm, LogError, MAX_BSGS_TABLE,
+};
+use thiserror::Error;
 
-        loop_size += 1;
-    }
+const MAGIC_NUMBER: u64 = 20201227;
+const DEFAULT_SUBJECT_NUMBER: u64 = 7;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum HandshakeError {
+    #[error("The modulus {0} is not a prime number")]
+    NotPrime(u64),
+    #[error("No loop size turns {subject} into either public key")]
+    NoLoopSize { subject: u64 },
+    #[error("Invalid public key: '{0}'")]
+    InvalidPublicKey(String),
+    #[error(transparent)]
+    Log(#[from] LogError),
 }
 
-fn transform(subject: usize, loop_size: usize) -> usize {
-    let mut acc = 1;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handshake {
+    pub encryption_key: u64,
+    pub loop_size: u64,
+    pub public_key: u64,
+    pub algorithm: Algorithm,
+}
 
-    for _ in 0..loop_size {
-        acc *= subject;
-        acc %= MAGIC_NUMBER;
+pub fn crack_handshake(
+    card_pub_key: u64,
+    door_pub_key: u64,
+    subject: u64,
+    modulus: u64,
+    algorithm: Option<Algorithm>,
+) -> Result<Handshake, HandshakeError> {
+    if !is_prime(modulus) {
+        return Err(HandshakeError::NotPrime(modulus));
     }
+    let algorithm = algorithm.unwrap_or_else(|| Algorithm::select(modulus));
 
-    acc
-}
+    for (public_key, other_pub_key) in [(card_pub_key, door_pub_key), (door_pub_key, card_pub_key)]
+    {
+        if let Some(loop_size) = discrete_log(subject, public_key, modulus, algorithm)? {
+            return Ok(Handshake {
+                encryption_key: pow_mod(other_pub_key, loop_size, modulus),
+                loop_size,
+                public_key,
+                algorithm,
+            });
+        }
+    }
 
-pub fn part1(input: &str) -> usize {
-    let mut lines = input.lines();
-    let card_pub_key: usize = lines.next().unwrap().parse().unwrap();
-    let door_pub_key: usize = lines.next().unwrap().parse().unwrap();
+    Err(HandshakeError::NoLoopSize { subject })
+}
 
-    let card_loop_size = calculate_loop_size(card_pub_key);
-    let door_loop_size = calculate_loop_size(door_pub_key);
+fn parse_keys(input: &str) -> Result<(u64, u64), HandshakeError> {
+    let mut keys = input.lines().map(|line| {
+        line.trim()
+            .parse()
+            .map_err(|_| HandshakeError::InvalidPublicKey(line.to_string()))
+    });
+    let mut next_key = || {
+        keys.next()
+            .unwrap_or_else(|| Err(HandshakeError::InvalidPublicKey("".to_string())))
+    };
+    Ok((next_key()?, next_key()?))
+}
 
-    transform(
-        transform(DEFAULT_SUBJECT_NUMBER, card_loop_size),
-        door_loop_size,
+pub fn crack(input: &str, algorithm: Option<Algorithm>) -> Result<Handshake, HandshakeError> {
+    let (card_pub_key, door_pub_key) = parse_keys(input)?;
+    crack_handshake(
+        card_pub_key,
+        door_pub_key,
+        DEFAULT_SUBJECT_NUMBER,
+        MAGIC_NUMBER,
+        algorithm,
     )
 }
 
+pub fn part1(input: &str) -> u64 {
+    crack(input, None).unwrap().encryption_key
+}
+
 #[cfg(test)]
 mod ex25_tests {
     use super::*;
 
     #[test]
     fn test_calculate_loop_size() {
-        assert_eq!(calculate_loop_size(5764801), 8);
-        assert_eq!(calculate_loop_size(17807724), 11);
+        for algorithm in [
+            Algorithm::BruteForce,
+            Algorithm::BabyStepGiantStep,
+            Algorithm::PohligHellman,
+        ] {
+            let loop_size = |n| discrete_log(DEFAULT_SUBJECT_NUMBER, n, MAGIC_NUMBER, algorithm);
+            assert_eq!(loop_size(5764801), Ok(Some(8)));
+            assert_eq!(loop_size(17807724), Ok(Some(11)));
+        }
     }
 
     #[test]
     fn test_transform() {
-        assert_eq!(transform(17807724, 8), 14897079);
-        assert_eq!(transform(5764801, 11), 14897079);
+        assert_eq!(pow_mod(17807724, 8, MAGIC_NUMBER), 14897079);
+        assert_eq!(pow_mod(5764801, 11, MAGIC_NUMBER), 14897079);
+    }
+
+    #[test]
+    fn test_crack_handshake() {
+        let handshake = crack("5764801\n17807724", Some(Algorithm::BabyStepGiantStep)).unwrap();
+        assert_eq!(
+            handshake,
+            Handshake {
+                encryption_key: 14897079,
+                loop_size: 8,
+                public_key: 5764801,
+                algorithm: Algorithm::BabyStepGiantStep,
+            }
+        );
+
+        // other parameters: subject 5 modulo the prime 2^31 - 1
+        let modulus = (1 << 31) - 1;
+        let card = pow_mod(5, 1_234_567, modulus);
+        let door = pow_mod(5, 987_654_321, modulus);
+        let handshake = crack_handshake(card, door, 5, modulus, None).unwrap();
+        assert_eq!(handshake.algorithm, Algorithm::PohligHellman);
+        assert_eq!(
+            handshake.encryption_key,
+            pow_mod(card, 987_654_321, modulus)
+        );
+    }
+
+    #[test]
+    fn test_crack_errors() {
+        assert_eq!(
+            crack_handshake(1, 2, 7, 20201228, None),
+            Err(HandshakeError::NotPrime(20201228))
+        );
+        // 2 only generates {1, 2, 4} modulo 7
+        assert_eq!(
+            crack_handshake(3, 5, 2, 7, None),
+            Err(HandshakeError::NoLoopSize { subject: 2 })
+        );
+        assert_eq!(
+            crack("123\nabc", None),
+            Err(HandshakeError::InvalidPublicKey("abc".to_string()))
+        );
+        assert_eq!(
+            crack("123", None),
+            Err(HandshakeError::InvalidPublicKey("".to_string()))
+        );
     }
 
     #[test]
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use thiserror::Error;
+
+// beyond this, the baby steps alone would take hundreds of MiB
+pub const MAX_BSGS_TABLE: u64 = 1 << 24;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LogError {
+    #[error("The modulus {0} is not a prime number")]
+    NotPrime(u64),
+    #[error("Baby-step giant-step would need a table of {0} entries")]
+    TableTooLarge(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    BruteForce,
+    BabyStepGiantStep,
+    // Pohlig–Hellman: one logarithm per prime factor of the order, combined with the CRT
+    PohligHellman,
+}
+
+impl Algorithm {
+    pub fn select(modulus: u64) -> Self {
+        if modulus < 1 << 10 {
+            return Algorithm::BruteForce;
+        }
+
+        let largest_factor = factorize(modulus - 1)
+            .last()
+            .map_or(1, |(factor, _)| *factor);
+        // when the order is (almost) prime there is nothing for Pohlig–Hellman to split
+        if largest_factor >= (modulus - 1) / 2 {
+            Algorithm::BabyStepGiantStep
+        } else {
+            Algorithm::PohligHellman
+        }
+    }
+}
+
+pub fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+pub fn pow_mod(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+pub fn inv_mod(a: u64, modulus: u64) -> u64 {
+    pow_mod(a, modulus - 2, modulus)
+}
+
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for p in WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut primes = vec![];
+    let mut factor = 2;
+    while factor < 1000 && factor <= n / factor {
+        while n.is_multiple_of(factor) {
+            n /= factor;
+            primes.push(factor);
+        }
+        factor += if factor == 2 { 1 } else { 2 };
+    }
+
+    // what is left has no factor below 1000
+    let mut composites = vec![n];
+    while let Some(n) = composites.pop() {
+        if n == 1 {
+            continue;
+        }
+        if n < 1_000_000 || is_prime(n) {
+            primes.push(n);
+        } else {
+            let divisor = pollard_rho(n);
+            composites.extend([divisor, n / divisor]);
+        }
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = vec![];
+    for prime in primes {
+        match factors.last_mut() {
+            Some((last, exp)) if *last == prime => *exp += 1,
+            _ => factors.push((prime, 1)),
+        }
+    }
+    factors
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// a non-trivial divisor of the odd composite `n`
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+pub fn order(base: u64, modulus: u64) -> u64 {
+    let mut order = modulus - 1;
+    for (factor, _) in factorize(modulus - 1) {
+        while order.is_multiple_of(factor) && pow_mod(base, order / factor, modulus) == 1 {
+            order /= factor;
+        }
+    }
+    order
+}
+
+pub fn discrete_log(
+    base: u64,
+    target: u64,
+    modulus: u64,
+    algorithm: Algorithm,
+) -> Result<Option<u64>, LogError> {
+    if !is_prime(modulus) {
+        return Err(LogError::NotPrime(modulus));
+    }
+    let base = base % modulus;
+    let target = target % modulus;
+    if base == 0 || target == 0 {
+        return Ok(match (base, target) {
+            (_, 1) => Some(0),
+            (0, 0) => Some(1),
+            _ => None,
+        });
+    }
+
+    let x = match algorithm {
+        Algorithm::BruteForce => brute_force(base, target, modulus, modulus - 1),
+        Algorithm::BabyStepGiantStep => bsgs(base, target, modulus, order(base, modulus))?,
+        Algorithm::PohligHellman => pohlig_hellman(base, target, modulus)?,
+    };
+    let x = match x {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    // the sub-algorithms find an answer modulo the order of `base`, which is already
+    // the smallest one, but Pohlig–Hellman can produce an answer for a `target` that is
+    // not in the subgroup generated by `base`, so double check it
+    Ok((pow_mod(base, x, modulus) == target).then_some(x))
+}
+
+fn brute_force(base: u64, target: u64, modulus: u64, limit: u64) -> Option<u64> {
+    let mut acc = 1;
+    for x in 0..limit {
+        if acc == target {
+            return Some(x);
+        }
+        acc = mul_mod(acc, base, modulus);
+    }
+    None
+}
+
+fn bsgs(base: u64, target: u64, modulus: u64, order: u64) -> Result<Option<u64>, LogError> {
+    let m = (order as f64).sqrt().ceil() as u64;
+    if m > MAX_BSGS_TABLE {
+        return Err(LogError::TableTooLarge(m));
+    }
+    let mut baby_steps: HashMap<u64, u64> = HashMap::with_capacity(m as usize);
+    let mut acc = 1;
+    for j in 0..m {
+        baby_steps.entry(acc).or_insert(j);
+        acc = mul_mod(acc, base, modulus);
+    }
+
+    let giant_step = inv_mod(pow_mod(base, m, modulus), modulus);
+    let mut gamma = target;
+    for i in 0..m {
+        if let Some(j) = baby_steps.get(&gamma) {
+            return Ok(Some(i * m + j));
+        }
+        gamma = mul_mod(gamma, giant_step, modulus);
+    }
+    Ok(None)
+}
+
+fn pohlig_hellman(base: u64, target: u64, modulus: u64) -> Result<Option<u64>, LogError> {
+    let order = order(base, modulus);
+    let mut residues = vec![];
+
+    for (factor, exp) in factorize(order) {
+        let prime_power = factor.pow(exp);
+        let cofactor = order / prime_power;
+        let sub_base = pow_mod(base, cofactor, modulus);
+        let sub_target = pow_mod(target, cofactor, modulus);
+        // element of order `factor`, used to find one base-`factor` digit at a time
+        let gamma = pow_mod(sub_base, prime_power / factor, modulus);
+        let sub_base_inv = inv_mod(sub_base, modulus);
+
+        let mut x = 0;
+        let mut factor_k = 1;
+        for k in 0..exp {
+            let shifted = mul_mod(pow_mod(sub_base_inv, x, modulus), sub_target, modulus);
+            let h = pow_mod(shifted, prime_power / factor_k / factor, modulus);
+            let digit = if factor < 64 {
+                brute_force(gamma, h, modulus, factor)
+            } else {
+                bsgs(gamma, h, modulus, factor)?
+            };
+            let digit = match digit {
+                Some(digit) => digit,
+                None => return Ok(None),
+            };
+            x += digit * factor_k;
+            if k + 1 < exp {
+                factor_k *= factor;
+            }
+        }
+        residues.push((x, prime_power));
+    }
+
+    Ok(Some(crt(&residues)))
+}
+
+fn crt(residues: &[(u64, u64)]) -> u64 {
+    let mut x: u128 = 0;
+    let mut m: u128 = 1;
+    for (r, mi) in residues {
+        let (r, mi) = (*r as u128, *mi as u128);
+        // find k so that x + k * m = r (mod mi)
+        let m_inv = mod_inverse_u128(m % mi, mi);
+        let diff = (r + mi - x % mi) % mi;
+        let k = diff * m_inv % mi;
+        x += k * m;
+        m *= mi;
+    }
+    x as u64
+}
+
+fn mod_inverse_u128(a: u128, m: u128) -> u128 {
+    if m == 1 {
+        return 0;
+    }
+    let (mut old_r, mut r) = (a as i128, m as i128);
+    let (mut old_s, mut s) = (1_i128, 0_i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    old_s.rem_euclid(m as i128) as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHMS: [Algorithm; 3] = [
+        Algorithm::BruteForce,
+        Algorithm::BabyStepGiantStep,
+        Algorithm::PohligHellman,
+    ];
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(pow_mod(7, 8, 20201227), 5764801);
+        assert_eq!(pow_mod(17807724, 8, 20201227), 14897079);
+        assert_eq!(pow_mod(5, 0, 7), 1);
+        assert_eq!(pow_mod(5, 3, 1), 0);
+        assert_eq!(
+            pow_mod(3, u64::MAX, 18446744073709551557),
+            pow_mod(
+                3,
+                u64::MAX % (18446744073709551557 - 1),
+                18446744073709551557
+            )
+        );
+    }
+
+    #[test]
+    fn test_is_prime_and_factorize() {
+        assert!(is_prime(20201227));
+        assert!(is_prime(18446744073709551557));
+        assert!(!is_prime(1));
+        assert!(!is_prime(20201227 * 3));
+        assert!(!is_prime(3215031751));
+        assert_eq!(factorize(20201226), [(2, 1), (3, 1), (29, 1), (116099, 1)]);
+        assert_eq!(factorize(720), [(2, 4), (3, 2), (5, 1)]);
+        // two 32-bit primes, out of reach of trial division
+        assert_eq!(
+            factorize(18446743979220271189),
+            [(4294967279, 1), (4294967291, 1)]
+        );
+        assert_eq!(
+            factorize(18446744073709551556),
+            [(2, 2), (11, 1), (137, 1), (547, 1), (5594472617641, 1)]
+        );
+        assert_eq!(factorize(1), []);
+        assert_eq!(order(7, 20201227), 20201226);
+        assert_eq!(order(2, 7), 3);
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        for algorithm in ALGORITHMS {
+            assert_eq!(discrete_log(7, 5764801, 20201227, algorithm), Ok(Some(8)));
+            assert_eq!(discrete_log(7, 17807724, 20201227, algorithm), Ok(Some(11)));
+            assert_eq!(discrete_log(7, 1, 20201227, algorithm), Ok(Some(0)));
+            // 2 only generates {1, 2, 4} modulo 7
+            assert_eq!(discrete_log(2, 4, 7, algorithm), Ok(Some(2)));
+            assert_eq!(discrete_log(2, 3, 7, algorithm), Ok(None));
+            // 8100 = 2^2 * 3^4 * 5^2 and 6 generates the whole group
+            assert_eq!(discrete_log(6, 7531, 8101, algorithm), Ok(Some(6689)));
+            assert_eq!(
+                discrete_log(7, 1, 20201228, algorithm),
+                Err(LogError::NotPrime(20201228))
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_modulus() {
+        // 2^61 - 1 is prime
+        let modulus = (1 << 61) - 1;
+        let target = pow_mod(37, 123_456, modulus);
+        assert_eq!(
+            discrete_log(37, target, modulus, Algorithm::PohligHellman),
+            Ok(Some(123_456))
+        );
+        assert_eq!(Algorithm::select(modulus), Algorithm::PohligHellman);
+        assert_eq!(Algorithm::select(20201227), Algorithm::PohligHellman);
+        // 2 * 1000151 + 1 is a safe prime: half of its group order is prime
+        assert_eq!(Algorithm::select(2000303), Algorithm::BabyStepGiantStep);
+        assert_eq!(Algorithm::select(101), Algorithm::BruteForce);
+
+        // half of the group order is a 62-bit prime: too large for the baby steps
+        let modulus = 9223372036854771239;
+        assert_eq!(Algorithm::select(modulus), Algorithm::BabyStepGiantStep);
+        for algorithm in [Algorithm::BabyStepGiantStep, Algorithm::PohligHellman] {
+            assert_eq!(
+                discrete_log(5, 3, modulus, algorithm),
+                Err(LogError::TableTooLarge(2147483648))
+            );
+        }
+    }
+}
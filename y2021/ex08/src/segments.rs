@@ -0,0 +1,260 @@
+use thiserror::Error;
+
+const SEGMENTS: usize = 7;
+
+pub type Pattern = u8;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("Invalid reading '{0}'")]
+    InvalidReading(String),
+    #[error("No wiring matches the readings")]
+    NoWiring,
+    #[error("More than one wiring matches the readings")]
+    Ambiguous,
+}
+
+pub fn parse_pattern(s: &str) -> Result<Pattern, DecodeError> {
+    let mut pattern = 0;
+    for c in s.chars() {
+        let bit = match c {
+            'a'..='g' => 1 << (c as u8 - b'a'),
+            _ => return Err(DecodeError::InvalidReading(s.to_string())),
+        };
+        if pattern & bit != 0 {
+            return Err(DecodeError::InvalidReading(s.to_string()));
+        }
+        pattern |= bit;
+    }
+    if pattern == 0 {
+        return Err(DecodeError::InvalidReading(s.to_string()));
+    }
+    Ok(pattern)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    digits: Vec<Pattern>,
+}
+
+impl Font {
+    pub fn new(digits: Vec<Pattern>) -> Self {
+        Font { digits }
+    }
+
+    pub fn decimal() -> Self {
+        let digits = [
+            "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+        ];
+        Font::new(digits.iter().map(|d| parse_pattern(d).unwrap()).collect())
+    }
+
+    pub fn hexadecimal() -> Self {
+        let mut font = Font::decimal();
+        for letter in ["abcdef", "bdefg", "abeg", "cdefg", "abdeg", "abde"] {
+            font.digits.push(parse_pattern(letter).unwrap());
+        }
+        font
+    }
+
+    pub fn base(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn value(&self, pattern: Pattern) -> Option<usize> {
+        self.digits.iter().position(|d| *d == pattern)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wiring([u8; SEGMENTS]);
+
+impl Wiring {
+    pub fn apply(&self, wires: Pattern) -> Pattern {
+        (0..SEGMENTS)
+            .filter(|w| wires & (1 << w) != 0)
+            .fold(0, |segments, w| segments | (1 << self.0[w]))
+    }
+
+    pub fn solve(readings: &[Pattern], font: &Font) -> Result<Wiring, DecodeError> {
+        let mut readings = readings.to_vec();
+        readings.sort_unstable();
+        readings.dedup();
+
+        // a wire lit in a reading can only go to a segment lit by some digit of the same
+        // size, and a wire off can only go to a segment that some such digit leaves off
+        let mut candidates = [(1 << SEGMENTS) - 1; SEGMENTS];
+        for reading in readings.iter() {
+            let size = reading.count_ones();
+            let same_size = font.digits.iter().filter(|d| d.count_ones() == size);
+            let (lit, off) = same_size.fold((0, 0), |(lit, off), d| (lit | d, off | !d));
+            for (w, candidate) in candidates.iter_mut().enumerate() {
+                *candidate &= if reading & (1 << w) != 0 { lit } else { off };
+            }
+        }
+
+        let mut search = Search {
+            readings: &readings,
+            font,
+            candidates,
+            wiring: [None; SEGMENTS],
+            found: vec![],
+        };
+        search.run(0);
+
+        match search.found[..] {
+            [] => Err(DecodeError::NoWiring),
+            [wiring] => Ok(wiring),
+            _ => Err(DecodeError::Ambiguous),
+        }
+    }
+}
+
+struct Search<'a> {
+    readings: &'a [Pattern],
+    font: &'a Font,
+    candidates: [Pattern; SEGMENTS],
+    wiring: [Option<u8>; SEGMENTS],
+    found: Vec<Wiring>,
+}
+
+impl Search<'_> {
+    fn consistent(&self) -> bool {
+        let assigned = (0..SEGMENTS)
+            .filter(|w| self.wiring[*w].is_some())
+            .fold(0, |wires, w| wires | (1 << w));
+        self.readings
+            .iter()
+            .filter(|r| *r & assigned == **r)
+            .all(|r| {
+                let segments = (0..SEGMENTS)
+                    .filter(|w| r & (1 << w) != 0)
+                    .fold(0, |s, w| s | (1 << self.wiring[w].unwrap()));
+                self.font.value(segments).is_some()
+            })
+    }
+
+    fn run(&mut self, used: Pattern) {
+        // two wirings are enough to know there is no single answer
+        if self.found.len() > 1 {
+            return;
+        }
+
+        // connect first the wire with the fewest segments left to pick from
+        let next = (0..SEGMENTS)
+            .filter(|w| self.wiring[*w].is_none())
+            .min_by_key(|w| (self.candidates[*w] & !used).count_ones());
+        let wire = match next {
+            None => {
+                self.found
+                    .push(Wiring(self.wiring.map(|segment| segment.unwrap())));
+                return;
+            }
+            Some(wire) => wire,
+        };
+
+        let free = self.candidates[wire] & !used;
+        for segment in (0..SEGMENTS as u8).filter(|s| free & (1 << s) != 0) {
+            self.wiring[wire] = Some(segment);
+            if self.consistent() {
+                self.run(used | (1 << segment));
+            }
+        }
+        self.wiring[wire] = None;
+    }
+}
+
+pub fn decode_entry(line: &str, font: &Font) -> Result<usize, DecodeError> {
+    let (raw_readings, raw_output_values) = line
+        .split_once(" | ")
+        .ok_or_else(|| DecodeError::InvalidReading(line.to_string()))?;
+    let readings = raw_readings
+        .split_whitespace()
+        .map(parse_pattern)
+        .collect::<Result<Vec<_>, _>>()?;
+    let outputs = raw_output_values
+        .split_whitespace()
+        .map(parse_pattern)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let all: Vec<Pattern> = readings
+        .iter()
+        .copied()
+        .chain(outputs.iter().copied())
+        .collect();
+    let wiring = Wiring::solve(&all, font)?;
+
+    // the outputs are among the readings the wiring was solved for, so they are digits
+    Ok(outputs.iter().fold(0, |value, pattern| {
+        value * font.base() + font.value(wiring.apply(*pattern)).unwrap()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(decode_entry(EXAMPLE, &Font::decimal()), Ok(5353));
+    }
+
+    #[test]
+    fn test_partial_and_repeated_readings() {
+        // 1, 4, 7, 8 and 0 (abcefg) are not enough...
+        let font = Font::decimal();
+        let readings: Vec<Pattern> = ["cf", "bcdf", "acf", "abcdefg", "abcefg", "cf"]
+            .iter()
+            .map(|r| parse_pattern(r).unwrap())
+            .collect();
+        assert_eq!(Wiring::solve(&readings, &font), Err(DecodeError::Ambiguous));
+
+        // ...but adding 2 (acdeg) and 5 (abdfg) settles it, even if wired as is
+        let line = "cf bcdf acf abcdefg cf | abcefg acdeg abdfg abdfg";
+        assert_eq!(decode_entry(line, &font), Ok(255));
+    }
+
+    #[test]
+    fn test_no_wiring() {
+        // two different readings cannot both be a 1...
+        let font = Font::decimal();
+        assert_eq!(
+            decode_entry("ab ac | ab", &font),
+            Err(DecodeError::NoWiring)
+        );
+        // ...and no digit has a single segment
+        assert_eq!(decode_entry("a | a", &font), Err(DecodeError::NoWiring));
+        assert_eq!(
+            decode_entry("ab | abz", &font),
+            Err(DecodeError::InvalidReading("abz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hexadecimal() {
+        let font = Font::hexadecimal();
+        assert_eq!(font.base(), 16);
+
+        // every digit on a display wired one wire to the left, so `a` lights segment `b`
+        let shift = |pattern: Pattern| ((pattern << 1) | (pattern >> 6)) & 0x7f;
+        let readings: Vec<String> = font
+            .digits
+            .iter()
+            .map(|d| {
+                let wires = (0..7).filter(|w| shift(*d) & (1 << w) != 0);
+                wires.map(|w| (b'a' + w) as char).collect()
+            })
+            .collect();
+        let line = format!(
+            "{} | {} {} {}",
+            readings.join(" "),
+            readings[0xC],
+            readings[0xA],
+            readings[0xF]
+        );
+        assert_eq!(decode_entry(&line, &font), Ok(0xCAF));
+    }
+}
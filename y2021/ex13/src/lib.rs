@@ -1,3 +1,4 @@
+use ocr::read_points;
 use std::{collections::HashSet, str::FromStr};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -89,7 +90,7 @@ pub fn part1(input: &str) -> usize {
     unique_points.len()
 }
 
-pub fn part2(input: &str) -> usize {
+fn fold_all(input: &str) -> HashSet<Point> {
     let (raw_points, raw_folds) = input.split_once("\n\n").unwrap();
     let mut points: Vec<Point> = raw_points
         .lines()
@@ -103,35 +104,18 @@ pub fn part2(input: &str) -> usize {
         });
     }
 
-    let unique_points: HashSet<Point> = points.into_iter().collect();
-
-    // Uncomment the following code to prints all the points to read the code:
-    //
-    // ###  ###   ##  #  # #### ###  #    ###
-    // #  # #  # #  # # #  #    #  # #    #  #
-    // #  # #  # #    ##   ###  ###  #    #  #
-    // ###  ###  #    # #  #    #  # #    ###
-    // # #  #    #  # # #  #    #  # #    # #
-    // #  # #     ##  #  # #    ###  #### #  #
-    //
-    // let max_x = unique_points.iter().max_by_key(|p| p.x).unwrap().x;
-    // let max_y = unique_points.iter().max_by_key(|p| p.y).unwrap().y;
-    // let mut grid: Vec<Vec<char>> = (0..=max_y).map(|_| vec![' '; max_x as usize + 1]).collect();
-
-    // for point in unique_points.iter() {
-    //     grid[point.y][point.x] = '#';
-    // }
-
-    // for line in grid {
-    //     println!("{}", line.as_slice().iter().collect::<String>());
-    // }
+    points.into_iter().collect()
+}
 
-    unique_points.len()
+pub fn part2(input: &str) -> String {
+    let points = fold_all(input);
+    read_points(points.into_iter().map(|p| (p.x, p.y))).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ocr::OcrError;
 
     #[test]
     fn test_fold_points() {
@@ -168,7 +152,17 @@ mod tests {
 fold along y=7
 fold along x=5";
         assert_eq!(part1(input), 17);
-        assert_eq!(part2(input), 16);
+
+        // the example draws a square, which is not a letter
+        let points = fold_all(input);
+        assert_eq!(points.len(), 16);
+        assert_eq!(
+            read_points(points.into_iter().map(|p| (p.x, p.y))),
+            Err(OcrError::UnsupportedHeight {
+                height: 5,
+                image: "#####\n#...#\n#...#\n#...#\n#####\n".to_string()
+            })
+        );
     }
 
     #[test]
@@ -180,6 +174,6 @@ fold along x=5";
     #[test]
     fn test_part2() {
         let input = include_str!("../input.txt");
-        assert_eq!(part2(input), 97);
+        assert_eq!(part2(input), "RPCKFBLR");
     }
 }
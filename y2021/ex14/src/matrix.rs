@@ -0,0 +1,114 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    size: usize,
+    cells: Vec<u128>,
+}
+
+impl Matrix {
+    pub fn zero(size: usize) -> Self {
+        Matrix {
+            size,
+            cells: vec![0; size * size],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Matrix::zero(size);
+        for i in 0..size {
+            matrix.cells[i * size + i] = 1;
+        }
+        matrix
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> u128 {
+        self.cells[row * self.size + column]
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: u128) {
+        self.cells[row * self.size + column] = value;
+    }
+
+    pub fn checked_mul(&self, other: &Matrix) -> Option<Matrix> {
+        debug_assert_eq!(self.size, other.size);
+        let mut product = Matrix::zero(self.size);
+        for i in 0..self.size {
+            for k in 0..self.size {
+                let a = self.get(i, k);
+                if a == 0 {
+                    continue;
+                }
+                for j in 0..self.size {
+                    let cell = &mut product.cells[i * self.size + j];
+                    *cell = cell.checked_add(a.checked_mul(other.get(k, j))?)?;
+                }
+            }
+        }
+        Some(product)
+    }
+
+    pub fn checked_pow(&self, mut exponent: u64) -> Option<Matrix> {
+        let mut result = Matrix::identity(self.size);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Some(result)
+    }
+
+    pub fn checked_apply(&self, vector: &[u128]) -> Option<Vec<u128>> {
+        debug_assert_eq!(self.size, vector.len());
+        (0..self.size)
+            .map(|i| {
+                vector.iter().enumerate().try_fold(0u128, |acc, (j, v)| {
+                    acc.checked_add(self.get(i, j).checked_mul(*v)?)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci() -> Matrix {
+        let mut matrix = Matrix::zero(2);
+        matrix.set(0, 0, 1);
+        matrix.set(0, 1, 1);
+        matrix.set(1, 0, 1);
+        matrix
+    }
+
+    #[test]
+    fn test_pow() {
+        let matrix = fibonacci();
+        assert_eq!(matrix.checked_pow(0), Some(Matrix::identity(2)));
+        assert_eq!(matrix.checked_pow(1), Some(matrix.clone()));
+        let power = matrix.checked_pow(90).unwrap();
+        assert_eq!(power.get(0, 1), 2880067194370816120);
+        assert_eq!(
+            power.checked_apply(&[1, 0]),
+            Some(vec![4660046610375530309, 2880067194370816120])
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // F(186) is the largest Fibonacci number that fits in a u128
+        let matrix = fibonacci();
+        let power = matrix.checked_pow(185).unwrap();
+        assert_eq!(power.get(0, 0), 332825110087067562321196029789634457848);
+        assert_eq!(matrix.checked_pow(186), None);
+        assert_eq!(power.checked_apply(&[1, 1]), None);
+    }
+}
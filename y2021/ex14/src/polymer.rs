@@ -0,0 +1,249 @@
+use crate::Matrix;
+use std::{collections::BTreeMap, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PolymerError {
+    #[error("Missing the blank line between the template and the rules")]
+    MissingRules,
+    #[error("Empty template")]
+    EmptyTemplate,
+    #[error("Invalid rule '{0}'")]
+    InvalidRule(String),
+    #[error("Several rules for the pair '{0}'")]
+    DuplicateRule(String),
+    #[error("Element counts overflow after {0} steps")]
+    Overflow(u64),
+}
+
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    elements: Vec<char>,
+    template: Vec<usize>,
+    step: Matrix,
+}
+
+impl FromStr for Polymer {
+    type Err = PolymerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (raw_template, raw_rules) = s.split_once("\n\n").ok_or(PolymerError::MissingRules)?;
+        let raw_template = raw_template.trim();
+        if raw_template.is_empty() {
+            return Err(PolymerError::EmptyTemplate);
+        }
+
+        let mut rules = vec![];
+        for line in raw_rules.lines().filter(|line| !line.trim().is_empty()) {
+            let invalid = || PolymerError::InvalidRule(line.to_string());
+            let (pair, insert) = line.split_once(" -> ").ok_or_else(invalid)?;
+            let pair: Vec<char> = pair.chars().collect();
+            let insert: Vec<char> = insert.chars().collect();
+            match (&pair[..], &insert[..]) {
+                ([a, b], [_]) if rules.iter().any(|(pair, _)| *pair == (*a, *b)) => {
+                    return Err(PolymerError::DuplicateRule(format!("{a}{b}")))
+                }
+                ([a, b], [c]) => rules.push(((*a, *b), *c)),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let mut elements: Vec<char> = raw_template
+            .chars()
+            .chain(rules.iter().flat_map(|((a, b), c)| [*a, *b, *c]))
+            .collect();
+        elements.sort_unstable();
+        elements.dedup();
+        let index = |c: char| elements.binary_search(&c).unwrap();
+        let n = elements.len();
+
+        // pairs without a rule stay as they are
+        let mut step = Matrix::identity(n * n);
+        for ((a, b), c) in rules {
+            let (a, b, c) = (index(a), index(b), index(c));
+            let pair = a * n + b;
+            step.set(pair, pair, 0);
+            for new_pair in [a * n + c, c * n + b] {
+                step.set(new_pair, pair, step.get(new_pair, pair) + 1);
+            }
+        }
+
+        Ok(Polymer {
+            template: raw_template.chars().map(index).collect(),
+            elements,
+            step,
+        })
+    }
+}
+
+impl Polymer {
+    // pairs are indexed `first * elements + second`
+    fn pair_counts(&self, steps: u64) -> Result<Vec<u128>, PolymerError> {
+        let n = self.elements.len();
+        let mut counts = vec![0; n * n];
+        for pair in self.template.windows(2) {
+            counts[pair[0] * n + pair[1]] += 1;
+        }
+
+        // only raise the pairs the template can lead to, so that rules which never apply
+        // cannot overflow the power
+        let mut reachable: Vec<usize> = (0..n * n).filter(|pair| counts[*pair] > 0).collect();
+        let mut next = 0;
+        while next < reachable.len() {
+            let pair = reachable[next];
+            for new_pair in 0..n * n {
+                if self.step.get(new_pair, pair) > 0 && !reachable.contains(&new_pair) {
+                    reachable.push(new_pair);
+                }
+            }
+            next += 1;
+        }
+        let mut step = Matrix::zero(reachable.len());
+        for (i, row) in reachable.iter().enumerate() {
+            for (j, column) in reachable.iter().enumerate() {
+                step.set(i, j, self.step.get(*row, *column));
+            }
+        }
+        let counts: Vec<u128> = reachable.iter().map(|pair| counts[*pair]).collect();
+
+        let reached = step
+            .checked_pow(steps)
+            .and_then(|operator| operator.checked_apply(&counts))
+            .ok_or(PolymerError::Overflow(steps))?;
+        let mut counts = vec![0; n * n];
+        for (pair, count) in reachable.into_iter().zip(reached) {
+            counts[pair] = count;
+        }
+        Ok(counts)
+    }
+
+    pub fn histogram(&self, steps: u64) -> Result<BTreeMap<char, u128>, PolymerError> {
+        let n = self.elements.len();
+        let mut histogram = BTreeMap::new();
+        // every element starts a pair, but the last one, which never moves
+        let last = *self.template.last().unwrap();
+        histogram.insert(self.elements[last], 1);
+        for (pair, count) in self.pair_counts(steps)?.into_iter().enumerate() {
+            if count > 0 {
+                let total = histogram.entry(self.elements[pair / n]).or_insert(0u128);
+                *total = total
+                    .checked_add(count)
+                    .ok_or(PolymerError::Overflow(steps))?;
+            }
+        }
+        Ok(histogram)
+    }
+
+    pub fn spread(&self, steps: u64) -> Result<u128, PolymerError> {
+        let histogram = self.histogram(steps)?;
+        Ok(histogram.values().max().unwrap() - histogram.values().min().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    fn count(polymer: &str) -> BTreeMap<char, u128> {
+        let mut histogram = BTreeMap::new();
+        for c in polymer.chars() {
+            *histogram.entry(c).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    #[test]
+    fn test_histogram() {
+        let polymer: Polymer = EXAMPLE.parse().unwrap();
+        let steps = [
+            "NNCB",
+            "NCNBCHB",
+            "NBCCNBBBCBHCB",
+            "NBBBCNCCNBBNBNBBCHBHHBCHB",
+            "NBBNBNBBCCNBCNCCNBBNBBNBBBNBBNBBCBHCBHHNHCBBCBHCB",
+        ];
+        for (step, expected) in steps.iter().enumerate() {
+            assert_eq!(polymer.histogram(step as u64), Ok(count(expected)));
+        }
+
+        let after_10 = polymer.histogram(10).unwrap();
+        assert_eq!(after_10.values().sum::<u128>(), 3073);
+        assert_eq!(
+            after_10,
+            BTreeMap::from([('B', 1749), ('C', 298), ('H', 161), ('N', 865)])
+        );
+        assert_eq!(polymer.spread(10), Ok(1749 - 161));
+        assert_eq!(polymer.spread(40), Ok(2192039569602 - 3849876073));
+    }
+
+    #[test]
+    fn test_many_steps() {
+        // the length about doubles at every step, so 10^12 steps cannot be counted...
+        let polymer: Polymer = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            polymer.histogram(1_000_000_000_000),
+            Err(PolymerError::Overflow(1_000_000_000_000))
+        );
+        assert!(polymer.histogram(120).is_ok());
+
+        // ...unless only some pairs grow: here every step adds an A and nothing else
+        let polymer: Polymer = "AB\n\nAB -> A".parse().unwrap();
+        assert_eq!(
+            polymer.histogram(1_000_000_000_000),
+            Ok(BTreeMap::from([('A', 1_000_000_000_001), ('B', 1)]))
+        );
+
+        // a rule that never applies does not change that
+        let polymer: Polymer = "AB\n\nAB -> A\nCC -> C".parse().unwrap();
+        assert_eq!(
+            polymer.histogram(200),
+            Ok(BTreeMap::from([('A', 201), ('B', 1)]))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "NNCB".parse::<Polymer>().unwrap_err(),
+            PolymerError::MissingRules
+        );
+        assert_eq!(
+            "\n\nCH -> B".parse::<Polymer>().unwrap_err(),
+            PolymerError::EmptyTemplate
+        );
+        assert_eq!(
+            "NNCB\n\nCH -> B\nCHB".parse::<Polymer>().unwrap_err(),
+            PolymerError::InvalidRule("CHB".to_string())
+        );
+        assert_eq!(
+            "NNCB\n\nCHH -> B"
+                .parse::<Polymer>()
+                .unwrap_err()
+                .to_string(),
+            "Invalid rule 'CHH -> B'"
+        );
+        assert_eq!(
+            "AB\n\nAB -> C\nAB -> D".parse::<Polymer>().unwrap_err(),
+            PolymerError::DuplicateRule("AB".to_string())
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::PacketError;
+
+#[derive(Debug, Clone)]
+pub struct BitReader {
+    bytes: Vec<u8>,
+    len: usize,
+    position: usize,
+}
+
+impl BitReader {
+    pub fn from_hex(hex: &str) -> Result<Self, PacketError> {
+        let mut bytes = vec![0; hex.len().div_ceil(2)];
+        for (i, c) in hex.chars().enumerate() {
+            let nibble = c.to_digit(16).ok_or(PacketError::InvalidHex(c))? as u8;
+            bytes[i / 2] |= if i % 2 == 0 { nibble << 4 } else { nibble };
+        }
+
+        Ok(BitReader {
+            bytes,
+            len: hex.len() * 4,
+            position: 0,
+        })
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    pub fn read(&mut self, bits: usize) -> Result<u64, PacketError> {
+        debug_assert!(bits <= 64);
+        if bits > self.remaining() {
+            return Err(PacketError::UnexpectedEnd);
+        }
+
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = (self.bytes[self.position / 8] >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn write(&mut self, value: u64, bits: usize) {
+        for i in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            self.bytes[self.len / 8] |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+    }
+
+    pub fn append(&mut self, other: &BitWriter) {
+        for i in 0..other.len {
+            let bit = (other.bytes[i / 8] >> (7 - i % 8)) & 1;
+            self.write(bit as u64, 1);
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        let digits = self.len.div_ceil(4);
+        (0..digits)
+            .map(|i| {
+                let byte = self.bytes[i / 2];
+                let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0xf };
+                char::from_digit(nibble as u32, 16)
+                    .unwrap()
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let mut reader = BitReader::from_hex("D2FE28").unwrap();
+        assert_eq!(reader.read(3), Ok(6));
+        assert_eq!(reader.read(3), Ok(4));
+        assert_eq!(reader.read(5), Ok(0b10111));
+        assert_eq!(reader.position(), 11);
+        assert_eq!(reader.remaining(), 13);
+        assert_eq!(reader.read(14), Err(PacketError::UnexpectedEnd));
+
+        // an odd number of digits does not add a phantom nibble
+        assert_eq!(BitReader::from_hex("ABC").unwrap().remaining(), 12);
+        assert_eq!(
+            BitReader::from_hex("AG").unwrap_err(),
+            PacketError::InvalidHex('G')
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut writer = BitWriter::new();
+        writer.write(6, 3);
+        writer.write(4, 3);
+        writer.write(0b101111111000101, 15);
+        assert_eq!(writer.len(), 21);
+        assert_eq!(writer.to_hex(), "D2FE28");
+
+        let mut prefix = BitWriter::new();
+        prefix.write(0b1, 1);
+        prefix.append(&writer);
+        assert_eq!(prefix.len(), 22);
+        assert_eq!(prefix.to_hex(), "E97F14");
+    }
+}
@@ -0,0 +1,721 @@
+use crate::bits::{BitReader, BitWriter};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+const LITERAL_TYPE_ID: u8 = 4;
+const MAX_VERSION: u8 = 7;
+// well below what the recursive decoder can take on the stack
+const MAX_DEPTH: usize = 256;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PacketError {
+    #[error("Invalid hex digit '{0}'")]
+    InvalidHex(char),
+    #[error("The transmission ends in the middle of a packet")]
+    UnexpectedEnd,
+    #[error("Unknown type id {0}")]
+    UnknownTypeId(u8),
+    #[error("Version {0} does not fit in 3 bits")]
+    InvalidVersion(u8),
+    #[error("Literal does not fit in 64 bits")]
+    LiteralTooLarge,
+    #[error("The sub-packets take {actual} bits instead of {declared}")]
+    LengthMismatch { declared: usize, actual: usize },
+    #[error("A length of {0} does not fit in the length field")]
+    LengthTooLarge(usize),
+    #[error("'{operator}' cannot take {count} sub-packets")]
+    Arity { operator: Operator, count: usize },
+    #[error("Invalid expression: {0}")]
+    Syntax(String),
+    #[error("Packets are nested more than {MAX_DEPTH} levels deep")]
+    TooDeep,
+    #[error("'{0}' overflows 64 bits")]
+    Overflow(Operator),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operator {
+    const ALL: [Operator; 7] = [
+        Operator::Sum,
+        Operator::Product,
+        Operator::Minimum,
+        Operator::Maximum,
+        Operator::GreaterThan,
+        Operator::LessThan,
+        Operator::EqualTo,
+    ];
+
+    pub fn from_type_id(type_id: u8) -> Result<Self, PacketError> {
+        match type_id {
+            0 => Ok(Operator::Sum),
+            1 => Ok(Operator::Product),
+            2 => Ok(Operator::Minimum),
+            3 => Ok(Operator::Maximum),
+            5 => Ok(Operator::GreaterThan),
+            6 => Ok(Operator::LessThan),
+            7 => Ok(Operator::EqualTo),
+            _ => Err(PacketError::UnknownTypeId(type_id)),
+        }
+    }
+
+    pub fn type_id(&self) -> u8 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        match self {
+            Operator::Sum | Operator::Product => true,
+            Operator::Minimum | Operator::Maximum => count > 0,
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo => count == 2,
+        }
+    }
+
+    fn apply(&self, values: &[u64]) -> Result<u64, PacketError> {
+        let value = match self {
+            Operator::Sum => values
+                .iter()
+                .try_fold(0_u64, |acc, value| acc.checked_add(*value)),
+            Operator::Product => values
+                .iter()
+                .try_fold(1_u64, |acc, value| acc.checked_mul(*value)),
+            Operator::Minimum => values.iter().min().copied(),
+            Operator::Maximum => values.iter().max().copied(),
+            Operator::GreaterThan => Some(u64::from(values[0] > values[1])),
+            Operator::LessThan => Some(u64::from(values[0] < values[1])),
+            Operator::EqualTo => Some(u64::from(values[0] == values[1])),
+        };
+        value.ok_or(PacketError::Overflow(*self))
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => "gt",
+            Operator::LessThan => "lt",
+            Operator::EqualTo => "eq",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Operator {
+    type Err = PacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Operator::ALL
+            .into_iter()
+            .find(|operator| operator.to_string() == s)
+            .ok_or_else(|| PacketError::Syntax(format!("unknown operator '{s}'")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    Bits,
+    Count,
+}
+
+impl LengthType {
+    fn field_bits(&self) -> usize {
+        match self {
+            LengthType::Bits => 15,
+            LengthType::Count => 11,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketData {
+    Literal(u64),
+    Operator(Operator, Vec<Packet>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    version: u8,
+    data: PacketData,
+}
+
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Result<Self, PacketError> {
+        if version > MAX_VERSION {
+            return Err(PacketError::InvalidVersion(version));
+        }
+        Ok(Packet {
+            version,
+            data: PacketData::Literal(value),
+        })
+    }
+
+    pub fn operator(
+        version: u8,
+        operator: Operator,
+        subpackets: Vec<Packet>,
+    ) -> Result<Self, PacketError> {
+        if version > MAX_VERSION {
+            return Err(PacketError::InvalidVersion(version));
+        }
+        if !operator.accepts(subpackets.len()) {
+            return Err(PacketError::Arity {
+                operator,
+                count: subpackets.len(),
+            });
+        }
+        Ok(Packet {
+            version,
+            data: PacketData::Operator(operator, subpackets),
+        })
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, PacketError> {
+        Decoder::new(hex)?.packet()
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn type_id(&self) -> u8 {
+        match &self.data {
+            PacketData::Literal(_) => LITERAL_TYPE_ID,
+            PacketData::Operator(operator, _) => operator.type_id(),
+        }
+    }
+
+    pub fn data(&self) -> &PacketData {
+        &self.data
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        let mut version_sum = self.version as u64;
+        if let PacketData::Operator(_, subpackets) = &self.data {
+            for packet in subpackets {
+                version_sum += packet.version_sum();
+            }
+        }
+
+        version_sum
+    }
+
+    pub fn eval(&self) -> Result<u64, PacketError> {
+        match &self.data {
+            PacketData::Literal(x) => Ok(*x),
+            PacketData::Operator(operator, subpackets) => {
+                let values = subpackets
+                    .iter()
+                    .map(Packet::eval)
+                    .collect::<Result<Vec<u64>, _>>()?;
+                operator.apply(&values)
+            }
+        }
+    }
+
+    pub fn encode(&self, length_type: LengthType) -> Result<String, PacketError> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer, length_type)?;
+        Ok(writer.to_hex())
+    }
+
+    fn write(&self, writer: &mut BitWriter, length_type: LengthType) -> Result<(), PacketError> {
+        writer.write(self.version as u64, 3);
+        writer.write(self.type_id() as u64, 3);
+
+        match &self.data {
+            PacketData::Literal(value) => {
+                // groups of 4 bits, the most significant first, all but the last one
+                // flagged with a leading 1
+                let groups = (64 - value.leading_zeros() as usize).div_ceil(4);
+                for group in (0..groups.max(1)).rev() {
+                    writer.write(u64::from(group > 0), 1);
+                    writer.write(value >> (4 * group), 4);
+                }
+            }
+            PacketData::Operator(_, subpackets) => {
+                let mut body = BitWriter::new();
+                for packet in subpackets {
+                    packet.write(&mut body, length_type)?;
+                }
+                let length = match length_type {
+                    LengthType::Bits => body.len(),
+                    LengthType::Count => subpackets.len(),
+                };
+                let field_bits = length_type.field_bits();
+                if length >= 1 << field_bits {
+                    return Err(PacketError::LengthTooLarge(length));
+                }
+
+                writer.write(u64::from(length_type == LengthType::Count), 1);
+                writer.write(length as u64, field_bits);
+                writer.append(&body);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fmt_version(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version {
+            0 => Ok(()),
+            version => write!(f, "@{version}"),
+        }
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.data {
+            PacketData::Literal(value) => {
+                write!(f, "{value}")?;
+                self.fmt_version(f)
+            }
+            PacketData::Operator(operator, subpackets) => {
+                write!(f, "({operator}")?;
+                self.fmt_version(f)?;
+                for packet in subpackets {
+                    write!(f, " {packet}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+fn split_version(atom: &str) -> Result<(&str, u8), PacketError> {
+    match atom.split_once('@') {
+        None => Ok((atom, 0)),
+        Some((name, version)) => {
+            let version = version
+                .parse()
+                .map_err(|_| PacketError::Syntax(format!("invalid version in '{atom}'")))?;
+            Ok((name, version))
+        }
+    }
+}
+
+fn parse_expression<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>,
+    depth: usize,
+) -> Result<Packet, PacketError> {
+    match tokens.next() {
+        None => Err(PacketError::Syntax(
+            "unexpected end of expression".to_string(),
+        )),
+        Some("(") => {
+            if depth >= MAX_DEPTH {
+                return Err(PacketError::TooDeep);
+            }
+            let head = tokens
+                .next()
+                .ok_or_else(|| PacketError::Syntax("missing operator".to_string()))?;
+            let (name, version) = split_version(head)?;
+            let operator = name.parse()?;
+            let mut subpackets = vec![];
+            while tokens.peek() != Some(&")") {
+                subpackets.push(parse_expression(tokens, depth + 1)?);
+            }
+            tokens.next();
+            Packet::operator(version, operator, subpackets)
+        }
+        Some(")") => Err(PacketError::Syntax("unexpected ')'".to_string())),
+        Some(atom) => {
+            let (value, version) = split_version(atom)?;
+            let value = value
+                .parse()
+                .map_err(|_| PacketError::Syntax(format!("invalid literal '{atom}'")))?;
+            Packet::literal(version, value)
+        }
+    }
+}
+
+impl FromStr for Packet {
+    type Err = PacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spaced = s.replace('(', " ( ").replace(')', " ) ");
+        let mut tokens = spaced.split_whitespace().peekable();
+        let packet = parse_expression(&mut tokens, 0)?;
+        match tokens.next() {
+            None => Ok(packet),
+            Some(token) => Err(PacketError::Syntax(format!("unexpected '{token}'"))),
+        }
+    }
+}
+
+struct Decoder {
+    reader: BitReader,
+    listing: Option<Vec<String>>,
+    depth: usize,
+}
+
+impl Decoder {
+    fn new(hex: &str) -> Result<Self, PacketError> {
+        Ok(Decoder {
+            reader: BitReader::from_hex(hex)?,
+            listing: None,
+            depth: 0,
+        })
+    }
+
+    fn note(&mut self, offset: usize, version: u8, description: String) {
+        if let Some(listing) = &mut self.listing {
+            let indent = "  ".repeat(self.depth);
+            listing.push(format!("{offset:>5}  {indent}v{version} {description}"));
+        }
+    }
+
+    fn packet(&mut self) -> Result<Packet, PacketError> {
+        let offset = self.reader.position();
+        let version = self.reader.read(3)? as u8;
+        let type_id = self.reader.read(3)? as u8;
+
+        if type_id == LITERAL_TYPE_ID {
+            let mut value: u64 = 0;
+            loop {
+                let more = self.reader.read(1)? == 1;
+                if value >> 60 != 0 {
+                    return Err(PacketError::LiteralTooLarge);
+                }
+                value = (value << 4) | self.reader.read(4)?;
+                if !more {
+                    break;
+                }
+            }
+            self.note(offset, version, format!("literal {value}"));
+            return Packet::literal(version, value);
+        }
+
+        let operator = Operator::from_type_id(type_id)?;
+        if self.depth >= MAX_DEPTH {
+            return Err(PacketError::TooDeep);
+        }
+        let length_type = match self.reader.read(1)? {
+            0 => LengthType::Bits,
+            _ => LengthType::Count,
+        };
+        let length = self.reader.read(length_type.field_bits())? as usize;
+        let unit = match length_type {
+            LengthType::Bits => "bits",
+            LengthType::Count => "packets",
+        };
+        self.note(offset, version, format!("{operator} ({length} {unit})"));
+
+        let mut subpackets = vec![];
+        self.depth += 1;
+        match length_type {
+            LengthType::Bits => {
+                let start = self.reader.position();
+                while self.reader.position() - start < length {
+                    subpackets.push(self.packet()?);
+                }
+                let actual = self.reader.position() - start;
+                if actual != length {
+                    return Err(PacketError::LengthMismatch {
+                        declared: length,
+                        actual,
+                    });
+                }
+            }
+            LengthType::Count => {
+                for _ in 0..length {
+                    subpackets.push(self.packet()?);
+                }
+            }
+        }
+        self.depth -= 1;
+
+        Packet::operator(version, operator, subpackets)
+    }
+}
+
+pub fn disassemble(hex: &str) -> Result<String, PacketError> {
+    let mut decoder = Decoder::new(hex)?;
+    decoder.listing = Some(vec![]);
+    decoder.packet()?;
+    Ok(decoder.listing.unwrap().join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(hex: &str) -> Packet {
+        Packet::from_hex(hex).unwrap()
+    }
+
+    #[test]
+    fn test_parse_simple_literal_packet() {
+        let packet = packet("D2FE28");
+        assert_eq!(packet.version(), 6);
+        assert_eq!(packet.type_id(), 4);
+        assert_eq!(packet.data(), &PacketData::Literal(2021));
+    }
+
+    #[test]
+    fn test_parse_simple_expression_packets() {
+        let cases = vec![
+            ("38006F45291200", "(lt@1 10@6 20@2)"),
+            ("EE00D40C823060", "(max@7 1@2 2@4 3@1)"),
+            ("8A004A801A8002F478", "(min@4 (min@1 (min@5 15@6)))"),
+        ];
+        for (hex, expected) in cases {
+            assert_eq!(packet(hex).to_string(), expected);
+        }
+
+        let cases = vec![
+            ("8A004A801A8002F478", 16),
+            ("620080001611562C8802118E34", 12),
+            ("C0015000016115A2E0802F182340", 23),
+            ("A0016C880162017C3686B18A3D4780", 31),
+        ];
+        for (hex, expected) in cases {
+            assert_eq!(packet(hex).version_sum(), expected);
+        }
+    }
+
+    #[test]
+    fn test_eval() {
+        let cases = vec![
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ];
+        for (hex, expected) in cases {
+            assert_eq!(packet(hex).eval(), Ok(expected), "Did not match for {hex}");
+        }
+
+        let big = Packet::literal(0, u64::MAX).unwrap();
+        let one = Packet::literal(0, 1).unwrap();
+        let sum = Packet::operator(0, Operator::Sum, vec![big.clone(), one.clone()]).unwrap();
+        assert_eq!(sum.eval(), Err(PacketError::Overflow(Operator::Sum)));
+        let product = Packet::operator(0, Operator::Product, vec![big.clone(), big]).unwrap();
+        assert_eq!(
+            product.eval().unwrap_err().to_string(),
+            "'product' overflows 64 bits"
+        );
+        // the overflow does not go away higher up
+        let min = Packet::operator(0, Operator::Minimum, vec![product, one]).unwrap();
+        assert_eq!(min.eval(), Err(PacketError::Overflow(Operator::Product)));
+    }
+
+    #[test]
+    fn test_encode() {
+        // the examples, encoded back the way they came but without the padding
+        assert_eq!(packet("D2FE28").encode(LengthType::Bits).unwrap(), "D2FE28");
+        assert_eq!(
+            packet("38006F45291200").encode(LengthType::Bits).unwrap(),
+            "38006F4529120"
+        );
+        assert_eq!(
+            packet("EE00D40C823060").encode(LengthType::Count).unwrap(),
+            "EE00D40C82306"
+        );
+
+        let built = Packet::operator(
+            3,
+            Operator::EqualTo,
+            vec![
+                Packet::operator(
+                    0,
+                    Operator::Product,
+                    vec![
+                        Packet::literal(1, 0).unwrap(),
+                        Packet::literal(2, u64::MAX).unwrap(),
+                    ],
+                )
+                .unwrap(),
+                Packet::operator(7, Operator::Sum, vec![]).unwrap(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            built.to_string(),
+            "(eq@3 (product 0@1 18446744073709551615@2) (sum@7))"
+        );
+        assert_eq!(built.eval(), Ok(1));
+        for length_type in [LengthType::Bits, LengthType::Count] {
+            let hex = built.encode(length_type).unwrap();
+            assert_eq!(Packet::from_hex(&hex), Ok(built.clone()));
+        }
+
+        // 11 bits for each literal
+        let wide =
+            Packet::operator(0, Operator::Sum, vec![Packet::literal(0, 1).unwrap(); 3000]).unwrap();
+        assert_eq!(
+            wide.encode(LengthType::Count),
+            Err(PacketError::LengthTooLarge(3000))
+        );
+        assert_eq!(
+            wide.encode(LengthType::Bits),
+            Err(PacketError::LengthTooLarge(33000))
+        );
+    }
+
+    #[test]
+    fn test_s_expressions() {
+        let expression = "(sum@2 1 (max 2 3@1) (gt 4@7 5))";
+        let packet: Packet = expression.parse().unwrap();
+        assert_eq!(packet.to_string(), expression);
+        assert_eq!(packet.eval(), Ok(4));
+        assert_eq!(
+            "( min 5  7 )".parse::<Packet>().map(|p| p.to_string()),
+            Ok("(min 5 7)".to_string())
+        );
+
+        assert_eq!(
+            "(lt 1)".parse::<Packet>(),
+            Err(PacketError::Arity {
+                operator: Operator::LessThan,
+                count: 1
+            })
+        );
+        assert_eq!(
+            "(max)".parse::<Packet>().unwrap_err().to_string(),
+            "'max' cannot take 0 sub-packets"
+        );
+        assert_eq!("1@8".parse::<Packet>(), Err(PacketError::InvalidVersion(8)));
+        assert!("(div 1 2)".parse::<Packet>().is_err());
+        assert!("(sum 1 2".parse::<Packet>().is_err());
+        assert!("(sum 1) 2".parse::<Packet>().is_err());
+        assert!("-1".parse::<Packet>().is_err());
+
+        let nested = |depth| format!("{}1{}", "(min ".repeat(depth), ")".repeat(depth));
+        assert!(nested(MAX_DEPTH).parse::<Packet>().is_ok());
+        assert_eq!(
+            nested(MAX_DEPTH + 1).parse::<Packet>(),
+            Err(PacketError::TooDeep)
+        );
+        assert_eq!(
+            nested(1_000_000).parse::<Packet>(),
+            Err(PacketError::TooDeep)
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(
+            disassemble("38006F45291200").unwrap(),
+            "    0  v1 lt (27 bits)\n   22    v6 literal 10\n   33    v2 literal 20"
+        );
+        assert_eq!(
+            disassemble("8A004A801A8002F478").unwrap(),
+            [
+                "    0  v4 min (1 packets)",
+                "   18    v1 min (1 packets)",
+                "   36      v5 min (11 bits)",
+                "   58        v6 literal 15",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(Packet::from_hex("D2FE"), Err(PacketError::UnexpectedEnd));
+        assert_eq!(
+            Packet::from_hex("D2FE2X"),
+            Err(PacketError::InvalidHex('X'))
+        );
+
+        // a comparison with a single sub-packet
+        let mut writer = BitWriter::new();
+        writer.write(0, 3);
+        writer.write(5, 3);
+        writer.write(1, 1);
+        writer.write(1, 11);
+        writer.write(0, 3);
+        writer.write(4, 3);
+        writer.write(0b00001, 5);
+        assert_eq!(
+            Packet::from_hex(&writer.to_hex()),
+            Err(PacketError::Arity {
+                operator: Operator::GreaterThan,
+                count: 1
+            })
+        );
+
+        // an outer length of 10 bits, but the literal inside takes 11
+        let mut writer = BitWriter::new();
+        writer.write(0, 3);
+        writer.write(0, 3);
+        writer.write(0, 1);
+        writer.write(10, 15);
+        writer.write(0, 3);
+        writer.write(4, 3);
+        writer.write(0b00001, 5);
+        assert_eq!(
+            Packet::from_hex(&writer.to_hex()),
+            Err(PacketError::LengthMismatch {
+                declared: 10,
+                actual: 11
+            })
+        );
+
+        // seventeen groups of 4 bits
+        let mut writer = BitWriter::new();
+        writer.write(0, 3);
+        writer.write(4, 3);
+        for _ in 0..16 {
+            writer.write(0b11111, 5);
+        }
+        writer.write(0b01111, 5);
+        assert_eq!(
+            Packet::from_hex(&writer.to_hex()),
+            Err(PacketError::LiteralTooLarge)
+        );
+
+        // min operators holding a single sub-packet, as deep as allowed and then far deeper
+        for (depth, expected) in [(MAX_DEPTH, Ok(1)), (100_000, Err(PacketError::TooDeep))] {
+            let mut writer = BitWriter::new();
+            for _ in 0..depth {
+                writer.write(0, 3);
+                writer.write(2, 3);
+                writer.write(1, 1);
+                writer.write(1, 11);
+            }
+            writer.write(0, 3);
+            writer.write(4, 3);
+            writer.write(0b00001, 5);
+            assert_eq!(
+                Packet::from_hex(&writer.to_hex()).and_then(|p| p.eval()),
+                expected
+            );
+        }
+
+        assert_eq!(
+            Operator::from_type_id(4),
+            Err(PacketError::UnknownTypeId(4))
+        );
+        assert_eq!(
+            Operator::from_type_id(8),
+            Err(PacketError::UnknownTypeId(8))
+        );
+    }
+}
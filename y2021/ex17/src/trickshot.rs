@@ -0,0 +1,404 @@
+use anyhow::{bail, Context, Result};
+use std::{ops::RangeInclusive, str::FromStr};
+
+// triangular(-n - 1) == triangular(n)
+fn triangular(n: i64) -> i64 {
+    n * (n + 1) / 2
+}
+
+fn triangular_root(value: i64) -> i64 {
+    if value <= 0 {
+        return 0;
+    }
+    let mut n = (((8 * value + 1) as f64).sqrt() as i64 - 1) / 2;
+    while triangular(n) < value {
+        n += 1;
+    }
+    while n > 0 && triangular(n - 1) >= value {
+        n -= 1;
+    }
+    n
+}
+
+// `e` steps away from where its velocity runs out, the probe is triangular(e) short of
+// where it stops (or turns back)
+fn offsets(peak: i64, lo: i64, hi: i64) -> Option<(i64, i64)> {
+    let first = triangular_root(triangular(peak) - hi);
+    let last = triangular_root(triangular(peak) - lo + 1) - 1;
+    (first <= last).then_some((first, last))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetArea {
+    pub min_x: i64,
+    pub max_x: i64,
+    pub min_y: i64,
+    pub max_y: i64,
+}
+
+impl TargetArea {
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+impl FromStr for TargetArea {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<TargetArea> {
+        let error_msg = "Input string not conform to spec: `target area: x={min_x}..{max_x}, y={min_y}..{max_y}`";
+
+        let (_, s) = s.split_once("target area: ").context(error_msg)?;
+        let (xrange, yrange) = s.trim().split_once(", ").context(error_msg)?;
+        let (xstart, xend) = xrange
+            .strip_prefix("x=")
+            .and_then(|r| r.split_once(".."))
+            .context(error_msg)?;
+        let (ystart, yend) = yrange
+            .strip_prefix("y=")
+            .and_then(|r| r.split_once(".."))
+            .context(error_msg)?;
+        let (x1, x2): (i64, i64) = (
+            xstart.parse().context("cannot parse min_x to i64")?,
+            xend.parse().context("cannot parse max_x to i64")?,
+        );
+        let (y1, y2): (i64, i64) = (
+            ystart.parse().context("cannot parse min_y to i64")?,
+            yend.parse().context("cannot parse max_y to i64")?,
+        );
+
+        Ok(TargetArea {
+            min_x: x1.min(x2),
+            max_x: x1.max(x2),
+            min_y: y1.min(y2),
+            max_y: y1.max(y2),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shot {
+    pub vx: i64,
+    pub vy: i64,
+}
+
+impl Shot {
+    pub fn new(vx: i64, vy: i64) -> Self {
+        Shot { vx, vy }
+    }
+
+    pub fn position(&self, step: i64) -> (i64, i64) {
+        let speed = self.vx.abs();
+        let x = triangular(speed) - triangular((speed - step).max(0));
+        let y = triangular(self.vy) - triangular(step - self.vy - 1);
+        (self.vx.signum() * x, y)
+    }
+
+    pub fn max_height(&self) -> i64 {
+        triangular(self.vy.max(0))
+    }
+
+    pub fn trajectory(&self, target: &TargetArea) -> Vec<(i64, i64)> {
+        let mut trajectory = vec![];
+        for step in 0.. {
+            let position = self.position(step);
+            trajectory.push(position);
+            if position.1 < target.min_y && step > self.vy {
+                break;
+            }
+        }
+        trajectory
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Steps {
+    pub first: i64,
+    pub last: Option<i64>,
+}
+
+impl Steps {
+    fn overlaps(&self, other: &Steps) -> bool {
+        self.last.is_none_or(|last| other.first <= last)
+            && other.last.is_none_or(|last| self.first <= last)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrickShots {
+    target: TargetArea,
+    vx_window: RangeInclusive<i64>,
+    vy_window: RangeInclusive<i64>,
+}
+
+impl TrickShots {
+    pub fn new(target: TargetArea) -> Result<Self> {
+        if target.contains(0, 0) {
+            bail!("The target area covers the launcher, so every shot hits it");
+        }
+
+        // going right, the probe reaches `x` only if it has enough speed to get there
+        // before stopping, and not so much that it jumps past it with the first step
+        let vx_window = if target.min_x > 0 {
+            triangular_root(target.min_x)
+        } else {
+            target.min_x
+        }..=if target.max_x < 0 {
+            -triangular_root(-target.max_x)
+        } else {
+            target.max_x
+        };
+
+        // going up, the same again; going down, the first step must not jump past the
+        // target, and neither may the first one below the launcher on the way back, at a
+        // speed of `vy + 1`
+        let min_vy = if target.min_y > 0 {
+            triangular_root(target.min_y)
+        } else {
+            target.min_y
+        };
+        let mut max_vy = target.max_y.max(-target.min_y - 1);
+
+        // falling back through the launcher height may hit as well, at step `2 * vy + 1`
+        if target.min_y <= 0 && target.max_y >= 0 {
+            let mut longest = 0;
+            for vx in vx_window.clone() {
+                match Self::x_steps_in(&target, vx) {
+                    Some(Steps { last: None, .. }) => bail!(
+                        "A probe launched at x velocity {vx} stops over the target area, \
+                        so any higher shot hits it when falling back"
+                    ),
+                    Some(Steps {
+                        last: Some(last), ..
+                    }) => longest = longest.max(last),
+                    None => {}
+                }
+            }
+            max_vy = max_vy.max((longest - 1) / 2);
+        }
+
+        Ok(TrickShots {
+            target,
+            vx_window,
+            vy_window: min_vy..=max_vy,
+        })
+    }
+
+    pub fn target(&self) -> &TargetArea {
+        &self.target
+    }
+
+    pub fn vx_window(&self) -> RangeInclusive<i64> {
+        self.vx_window.clone()
+    }
+
+    pub fn vy_window(&self) -> RangeInclusive<i64> {
+        self.vy_window.clone()
+    }
+
+    fn x_steps_in(target: &TargetArea, vx: i64) -> Option<Steps> {
+        // going left is going right in a mirror
+        let (speed, lo, hi) = if vx < 0 {
+            (-vx, -target.max_x, -target.min_x)
+        } else {
+            (vx, target.min_x, target.max_x)
+        };
+        let (first, last) = offsets(speed, lo, hi)?;
+        if first > speed {
+            return None;
+        }
+
+        Some(Steps {
+            first: speed - last.min(speed),
+            // stopping in range, it stays there
+            last: (first > 0).then_some(speed - first),
+        })
+    }
+
+    pub fn x_steps(&self, vx: i64) -> Option<Steps> {
+        Self::x_steps_in(&self.target, vx)
+    }
+
+    pub fn y_steps(&self, vy: i64) -> Vec<Steps> {
+        let (first, last) = match offsets(vy, self.target.min_y, self.target.max_y) {
+            Some(offsets) => offsets,
+            None => return vec![],
+        };
+
+        let mut steps = vec![];
+        // going up, `e` steps before the top at step `vy`
+        if first <= last.min(vy) {
+            steps.push(Steps {
+                first: vy - last.min(vy),
+                last: Some(vy - first),
+            });
+        }
+        // coming down, `e` steps after the top, which is before the launch when `vy < 0`
+        let first = first.max(-vy - 1);
+        if first <= last {
+            steps.push(Steps {
+                first: vy + 1 + first,
+                last: Some(vy + 1 + last),
+            });
+        }
+        steps
+    }
+
+    pub fn hits(&self, shot: &Shot) -> bool {
+        match self.x_steps(shot.vx) {
+            Some(x_steps) => self
+                .y_steps(shot.vy)
+                .iter()
+                .any(|y_steps| y_steps.overlaps(&x_steps)),
+            None => false,
+        }
+    }
+
+    pub fn shots(&self) -> impl Iterator<Item = Shot> + '_ {
+        let y_steps: Vec<(i64, Vec<Steps>)> = self
+            .vy_window()
+            .map(|vy| (vy, self.y_steps(vy)))
+            .filter(|(_, steps)| !steps.is_empty())
+            .collect();
+
+        self.vx_window()
+            .filter_map(|vx| self.x_steps(vx).map(|x_steps| (vx, x_steps)))
+            .flat_map(move |(vx, x_steps)| {
+                y_steps
+                    .iter()
+                    .filter(move |(_, steps)| steps.iter().any(|s| s.overlaps(&x_steps)))
+                    .map(move |(vy, _)| Shot::new(vx, *vy))
+                    .collect::<Vec<_>>()
+            })
+    }
+
+    pub fn highest(&self) -> Option<Shot> {
+        self.shots().max_by_key(|shot| shot.max_height())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn target(min_x: i64, max_x: i64, min_y: i64, max_y: i64) -> TargetArea {
+        TargetArea {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        }
+    }
+
+    fn brute_force(target: &TargetArea) -> HashSet<Shot> {
+        let mut shots = HashSet::new();
+        for vx in -60..=60 {
+            for vy in -60..=60 {
+                let shot = Shot::new(vx, vy);
+                if (0..500).any(|step| {
+                    let (x, y) = shot.position(step);
+                    target.contains(x, y)
+                }) {
+                    shots.insert(shot);
+                }
+            }
+        }
+        shots
+    }
+
+    #[test]
+    fn test_triangular() {
+        assert_eq!(triangular(4), 10);
+        assert_eq!(triangular(-5), 10);
+        assert_eq!(triangular_root(10), 4);
+        assert_eq!(triangular_root(11), 5);
+        assert_eq!(triangular_root(-3), 0);
+        assert_eq!(triangular_root(triangular(1414213)), 1414213);
+        assert_eq!(triangular_root(triangular(1414213) + 1), 1414214);
+    }
+
+    #[test]
+    fn test_example() {
+        let target: TargetArea = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let shots = TrickShots::new(target).unwrap();
+        assert_eq!(shots.vx_window(), 6..=30);
+        assert_eq!(shots.vy_window(), -10..=9);
+        assert_eq!(shots.shots().count(), 112);
+        assert_eq!(shots.highest().map(|shot| shot.vy), Some(9));
+        assert_eq!(Shot::new(6, 9).max_height(), 45);
+        assert!(shots.hits(&Shot::new(9, 0)));
+        assert!(!shots.hits(&Shot::new(17, -4)));
+
+        assert_eq!(
+            shots.x_steps(7),
+            Some(Steps {
+                first: 4,
+                last: None
+            })
+        );
+        assert_eq!(
+            shots.y_steps(2),
+            vec![Steps {
+                first: 7,
+                last: Some(7)
+            }]
+        );
+        assert_eq!(
+            Shot::new(7, 2).trajectory(&target),
+            [
+                (0, 0),
+                (7, 2),
+                (13, 3),
+                (18, 3),
+                (22, 2),
+                (25, 0),
+                (27, -3),
+                (28, -7),
+                (28, -12)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let targets = [
+            target(20, 30, -10, -5),
+            target(-30, -20, -10, -5),
+            target(20, 30, 5, 10),
+            target(-30, -20, 5, 10),
+            target(-5, 5, -10, -5),
+            target(-5, 5, 15, 21),
+            target(7, 8, -5, 5),
+            target(-5, -4, -1, 0),
+        ];
+        for target in targets {
+            let shots = TrickShots::new(target).unwrap();
+            let found: HashSet<Shot> = shots.shots().collect();
+            assert_eq!(found, brute_force(&target), "Did not match for {target:?}");
+            for shot in found.iter() {
+                assert!(shots.vx_window().contains(&shot.vx));
+                assert!(shots.vy_window().contains(&shot.vy));
+            }
+        }
+
+        // the left twin of the example goes as high
+        let shots = TrickShots::new(target(-30, -20, -10, -5)).unwrap();
+        assert_eq!(shots.highest().unwrap().max_height(), 45);
+    }
+
+    #[test]
+    fn test_infinite() {
+        assert!(TrickShots::new(target(-1, 1, -1, 1)).is_err());
+        // stopping at x = 21 and dropping through y = 0 at every height
+        assert!(TrickShots::new(target(20, 30, -5, 5)).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let target: TargetArea = "target area: x=-5..-30, y=10..5\n".parse().unwrap();
+        assert_eq!(target, self::target(-30, -5, 5, 10));
+        assert!("target area: x=1..2".parse::<TargetArea>().is_err());
+        assert!("target area: x=1..2, z=3..4".parse::<TargetArea>().is_err());
+    }
+}
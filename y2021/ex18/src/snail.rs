@@ -0,0 +1,511 @@
+use std::{fmt, ops::Add, str::FromStr};
+use thiserror::Error;
+
+type NodeId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Node {
+    Regular(u32),
+    Pair(NodeId, NodeId),
+}
+
+#[derive(Debug, Clone)]
+pub struct SnailNumber {
+    nodes: Vec<Node>,
+    root: NodeId,
+    // slots left behind by explosions, reused by the next splits
+    free: Vec<NodeId>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseSnailError {
+    #[error("Unexpected '{found}' at {position}, expected {expected}")]
+    Unexpected {
+        position: usize,
+        found: char,
+        expected: &'static str,
+    },
+    #[error("Unexpected end of input")]
+    UnexpectedEnd,
+    #[error("The number at {position} does not fit in 32 bits")]
+    NumberTooLarge { position: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Explode { left: u32, right: u32 },
+    Split { value: u32 },
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Explode { left, right } => write!(f, "explode [{left},{right}]"),
+            Step::Split { value } => write!(f, "split {value}"),
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    nodes: Vec<Node>,
+}
+
+impl Parser<'_> {
+    fn expect(&mut self, wanted: char, expected: &'static str) -> Result<(), ParseSnailError> {
+        match self.chars.next() {
+            Some((_, c)) if c == wanted => Ok(()),
+            Some((position, found)) => Err(ParseSnailError::Unexpected {
+                position,
+                found,
+                expected,
+            }),
+            None => Err(ParseSnailError::UnexpectedEnd),
+        }
+    }
+
+    fn element(&mut self) -> Result<NodeId, ParseSnailError> {
+        let node = match self.chars.peek() {
+            None => return Err(ParseSnailError::UnexpectedEnd),
+            Some((_, '[')) => {
+                self.chars.next();
+                let left = self.element()?;
+                self.expect(',', "','")?;
+                let right = self.element()?;
+                self.expect(']', "']'")?;
+                Node::Pair(left, right)
+            }
+            Some((position, c)) if c.is_ascii_digit() => {
+                let position = *position;
+                let mut value: u32 = 0;
+                while let Some(digit) = self.chars.peek().and_then(|(_, c)| c.to_digit(10)) {
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit))
+                        .ok_or(ParseSnailError::NumberTooLarge { position })?;
+                    self.chars.next();
+                }
+                Node::Regular(value)
+            }
+            Some((position, found)) => {
+                return Err(ParseSnailError::Unexpected {
+                    position: *position,
+                    found: *found,
+                    expected: "'[' or a number",
+                })
+            }
+        };
+        self.nodes.push(node);
+        Ok(self.nodes.len() - 1)
+    }
+}
+
+impl FromStr for SnailNumber {
+    type Err = ParseSnailError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.trim().char_indices().peekable(),
+            nodes: vec![],
+        };
+        let root = parser.element()?;
+        if let Some((position, found)) = parser.chars.next() {
+            return Err(ParseSnailError::Unexpected {
+                position,
+                found,
+                expected: "the end of the input",
+            });
+        }
+
+        Ok(SnailNumber {
+            nodes: parser.nodes,
+            root,
+            free: vec![],
+        })
+    }
+}
+
+impl SnailNumber {
+    pub fn magnitude(&self) -> u32 {
+        self.magnitude_of(self.root)
+    }
+
+    fn magnitude_of(&self, id: NodeId) -> u32 {
+        match self.nodes[id] {
+            Node::Regular(value) => value,
+            Node::Pair(left, right) => 3 * self.magnitude_of(left) + 2 * self.magnitude_of(right),
+        }
+    }
+
+    fn walk(&self) -> (Vec<NodeId>, Vec<(NodeId, usize)>) {
+        let mut regulars = vec![];
+        let mut pairs = vec![];
+        let mut stack = vec![(self.root, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            match self.nodes[id] {
+                Node::Regular(_) => regulars.push(id),
+                Node::Pair(left, right) => {
+                    pairs.push((id, depth));
+                    stack.push((right, depth + 1));
+                    stack.push((left, depth + 1));
+                }
+            }
+        }
+        (regulars, pairs)
+    }
+
+    fn value(&self, id: NodeId) -> u32 {
+        match self.nodes[id] {
+            Node::Regular(value) => value,
+            Node::Pair(..) => unreachable!("node {id} is not a regular number"),
+        }
+    }
+
+    fn alloc(&mut self, node: Node) -> NodeId {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn add_to(&mut self, id: NodeId, amount: u32) {
+        if let Node::Regular(value) = &mut self.nodes[id] {
+            *value += amount;
+        }
+    }
+
+    pub fn step(&mut self) -> Option<Step> {
+        let (regulars, pairs) = self.walk();
+
+        // pairs come out in the same left to right order, so the first one nested inside
+        // four pairs is the leftmost one; deeper inputs explode from the bottom up
+        let exploding = pairs.iter().find_map(|(id, depth)| match self.nodes[*id] {
+            Node::Pair(left, right) if *depth >= 4 => match (self.nodes[left], self.nodes[right]) {
+                (Node::Regular(_), Node::Regular(_)) => Some((*id, left, right)),
+                _ => None,
+            },
+            _ => None,
+        });
+        if let Some((id, left_id, right_id)) = exploding {
+            let (left, right) = (self.value(left_id), self.value(right_id));
+            let index = regulars.iter().position(|r| *r == left_id).unwrap();
+            if index > 0 {
+                self.add_to(regulars[index - 1], left);
+            }
+            if let Some(next) = regulars.get(index + 2) {
+                self.add_to(*next, right);
+            }
+            self.nodes[id] = Node::Regular(0);
+            self.free.extend([left_id, right_id]);
+            return Some(Step::Explode { left, right });
+        }
+
+        let id = *regulars.iter().find(|id| self.value(**id) >= 10)?;
+        let value = self.value(id);
+        let left = self.alloc(Node::Regular(value / 2));
+        let right = self.alloc(Node::Regular(value - value / 2));
+        self.nodes[id] = Node::Pair(left, right);
+        Some(Step::Split { value })
+    }
+
+    pub fn reduce_steps(self) -> ReduceSteps {
+        ReduceSteps { number: self }
+    }
+
+    pub fn reduce(self) -> SnailNumber {
+        let mut steps = self.reduce_steps();
+        steps.by_ref().for_each(drop);
+        steps.into_number()
+    }
+
+    fn copy_into(&self, id: NodeId, nodes: &mut Vec<Node>) -> NodeId {
+        let node = match self.nodes[id] {
+            Node::Regular(value) => Node::Regular(value),
+            Node::Pair(left, right) => {
+                let left = self.copy_into(left, nodes);
+                let right = self.copy_into(right, nodes);
+                Node::Pair(left, right)
+            }
+        };
+        nodes.push(node);
+        nodes.len() - 1
+    }
+
+    fn fmt_node(&self, id: NodeId, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.nodes[id] {
+            Node::Regular(value) => write!(f, "{value}"),
+            Node::Pair(left, right) => {
+                write!(f, "[")?;
+                self.fmt_node(left, f)?;
+                write!(f, ",")?;
+                self.fmt_node(right, f)?;
+                write!(f, "]")
+            }
+        }
+    }
+
+    fn eq_node(&self, id: NodeId, other: &SnailNumber, other_id: NodeId) -> bool {
+        match (self.nodes[id], other.nodes[other_id]) {
+            (Node::Regular(a), Node::Regular(b)) => a == b,
+            (Node::Pair(a_left, a_right), Node::Pair(b_left, b_right)) => {
+                self.eq_node(a_left, other, b_left) && self.eq_node(a_right, other, b_right)
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct ReduceSteps {
+    number: SnailNumber,
+}
+
+impl ReduceSteps {
+    pub fn number(&self) -> &SnailNumber {
+        &self.number
+    }
+
+    pub fn into_number(self) -> SnailNumber {
+        self.number
+    }
+}
+
+impl Iterator for ReduceSteps {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.number.step()
+    }
+}
+
+impl PartialEq for SnailNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_node(self.root, other, other.root)
+    }
+}
+
+impl Eq for SnailNumber {}
+
+impl fmt::Display for SnailNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_node(self.root, f)
+    }
+}
+
+impl Add for &SnailNumber {
+    type Output = SnailNumber;
+
+    fn add(self, rhs: Self) -> SnailNumber {
+        let mut nodes = Vec::with_capacity(self.nodes.len() + rhs.nodes.len() + 1);
+        let left = self.copy_into(self.root, &mut nodes);
+        let right = rhs.copy_into(rhs.root, &mut nodes);
+        nodes.push(Node::Pair(left, right));
+        let root = nodes.len() - 1;
+        SnailNumber {
+            nodes,
+            root,
+            free: vec![],
+        }
+        .reduce()
+    }
+}
+
+impl Add for SnailNumber {
+    type Output = SnailNumber;
+
+    fn add(self, rhs: Self) -> SnailNumber {
+        &self + &rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snail(s: &str) -> SnailNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for s in [
+            "[1,2]",
+            "[[1,2],3]",
+            "[[[[1,2],[3,4]],[[5,6],[7,8]]],9]",
+            "[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]",
+            "[10,[123,0]]",
+        ] {
+            assert_eq!(snail(s).to_string(), s);
+        }
+        assert_ne!(snail("[[1,2],3]"), snail("[1,[2,3]]"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "[1,2".parse::<SnailNumber>().unwrap_err(),
+            ParseSnailError::UnexpectedEnd
+        );
+        assert_eq!(
+            "[1;2]".parse::<SnailNumber>().unwrap_err(),
+            ParseSnailError::Unexpected {
+                position: 2,
+                found: ';',
+                expected: "','"
+            }
+        );
+        assert_eq!(
+            "[1,2]]".parse::<SnailNumber>().unwrap_err().to_string(),
+            "Unexpected ']' at 5, expected the end of the input"
+        );
+        assert!("[a,2]".parse::<SnailNumber>().is_err());
+        assert_eq!(snail("[4294967295,0]").to_string(), "[4294967295,0]");
+        assert_eq!(
+            "[1,4294967296]".parse::<SnailNumber>().unwrap_err(),
+            ParseSnailError::NumberTooLarge { position: 3 }
+        );
+    }
+
+    #[test]
+    fn test_magnitude() {
+        let cases = vec![
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                snail(input).magnitude(),
+                expected,
+                "Did not match for {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let cases = vec![
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]", Some((9, 8))),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]", Some((3, 2))),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]", Some((3, 2))),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+                Some((3, 2)),
+            ),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                Some((7, 3)),
+            ),
+            ("[[[[0,9],2],3],4]", "[[[[0,9],2],3],4]", None),
+            (
+                "[[[[[[1,2],3],4],5],6],7]",
+                "[[[[[0,5],4],5],6],7]",
+                Some((1, 2)),
+            ),
+        ];
+
+        for (input, expected, exploded) in cases {
+            let mut number = snail(input);
+            let step = number.step();
+            assert_eq!(
+                step,
+                exploded.map(|(left, right)| Step::Explode { left, right })
+            );
+            assert_eq!(number.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_reduce_too_deep() {
+        let number = snail("[[[[[[1,2],3],4],5],6],7]").reduce();
+        assert_eq!(number.to_string(), "[[[[0,9],5],6],7]");
+    }
+
+    #[test]
+    fn test_reduce_steps() {
+        let unreduced = snail("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        let mut steps = unreduced.reduce_steps();
+        let mut trace = vec![];
+        while let Some(step) = steps.next() {
+            trace.push(format!("{step}: {}", steps.number()));
+        }
+        assert_eq!(
+            trace,
+            [
+                "explode [4,3]: [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "explode [8,4]: [[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "split 15: [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "split 13: [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "explode [6,7]: [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+        assert_eq!(
+            steps.into_number(),
+            &snail("[[[[4,3],4],4],[7,[[8,4],9]]]") + &snail("[1,1]")
+        );
+    }
+
+    #[test]
+    fn test_reduce_reuses_nodes() {
+        // a large number keeps splitting and exploding for a long while, but the pairs
+        // never go deeper than 5 levels, 63 nodes at most
+        let mut steps = snail("[[[[0,0],0],0],10000]").reduce_steps();
+        let mut count = 0;
+        while steps.next().is_some() {
+            assert!(steps.number().nodes.len() <= 63);
+            count += 1;
+        }
+        assert!(count > 100);
+
+        let number = steps.into_number();
+        let (regulars, pairs) = number.walk();
+        assert_eq!(
+            number.nodes.len(),
+            regulars.len() + pairs.len() + number.free.len()
+        );
+    }
+
+    #[test]
+    fn test_add() {
+        let cases = vec![
+            (
+                "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+                "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+                "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]",
+            ),
+            (
+                "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]",
+                "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+                "[[[[6,7],[6,7]],[[7,7],[0,7]]],[[[8,7],[7,7]],[[8,8],[8,0]]]]",
+            ),
+            (
+                "[[[[6,7],[6,7]],[[7,7],[0,7]]],[[[8,7],[7,7]],[[8,8],[8,0]]]]",
+                "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+                "[[[[7,0],[7,7]],[[7,7],[7,8]]],[[[7,7],[8,8]],[[7,7],[8,7]]]]",
+            ),
+            (
+                "[[[[7,0],[7,7]],[[7,7],[7,8]]],[[[7,7],[8,8]],[[7,7],[8,7]]]]",
+                "[7,[5,[[3,8],[1,4]]]]",
+                "[[[[7,7],[7,8]],[[9,5],[8,7]]],[[[6,8],[0,8]],[[9,9],[9,0]]]]",
+            ),
+        ];
+
+        for (left, right, expected) in cases {
+            assert_eq!((snail(left) + snail(right)).to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{Point3D, Scanner, Transform};
+use std::collections::{HashSet, VecDeque};
+use thiserror::Error;
+
+pub const DEFAULT_MIN_OVERLAP: usize = 12;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AlignError {
+    #[error("No scanners to align")]
+    NoScanners,
+    #[error("Cannot align scanners {0:?} with scanner 0")]
+    Unaligned(Vec<usize>),
+    #[error("Scanners need at least 3 beacons in common to be aligned, not {0}")]
+    MinOverlapTooSmall(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct ScannerMap {
+    transforms: Vec<Transform>,
+    parents: Vec<Option<usize>>,
+    beacons: HashSet<Point3D>,
+}
+
+impl ScannerMap {
+    pub fn transform(&self, scanner: usize) -> &Transform {
+        &self.transforms[scanner]
+    }
+
+    pub fn position(&self, scanner: usize) -> Point3D {
+        self.transforms[scanner].translation
+    }
+
+    pub fn parent(&self, scanner: usize) -> Option<usize> {
+        self.parents[scanner]
+    }
+
+    pub fn path(&self, scanner: usize) -> Vec<usize> {
+        let mut path = vec![scanner];
+        while let Some(parent) = self.parents[path[path.len() - 1]] {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn beacons(&self) -> &HashSet<Point3D> {
+        &self.beacons
+    }
+
+    pub fn max_distance(&self) -> i32 {
+        let mut max_distance = 0;
+        for pos1 in self.transforms.iter().map(|t| t.translation) {
+            for pos2 in self.transforms.iter().map(|t| t.translation) {
+                let distance =
+                    (pos1.0 - pos2.0).abs() + (pos1.1 - pos2.1).abs() + (pos1.2 - pos2.2).abs();
+                max_distance = distance.max(max_distance);
+            }
+        }
+        max_distance
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Aligner {
+    min_overlap: usize,
+}
+
+impl Default for Aligner {
+    fn default() -> Self {
+        Aligner {
+            min_overlap: DEFAULT_MIN_OVERLAP,
+        }
+    }
+}
+
+impl Aligner {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_min_overlap(self, min_overlap: usize) -> Result<Self, AlignError> {
+        // two beacons still fit a half turn around the line through them
+        if min_overlap < 3 {
+            return Err(AlignError::MinOverlapTooSmall(min_overlap));
+        }
+        Ok(Aligner { min_overlap })
+    }
+
+    // `k` common beacons make `k * (k - 1) / 2` common distances
+    pub fn align(&self, scanners: &[Scanner]) -> Result<ScannerMap, AlignError> {
+        if scanners.is_empty() {
+            return Err(AlignError::NoScanners);
+        }
+
+        let fingerprints: Vec<_> = scanners.iter().map(Scanner::fingerprint).collect();
+        let min_shared = self.min_overlap * self.min_overlap.saturating_sub(1) / 2;
+
+        let mut transforms: Vec<Option<Transform>> = vec![None; scanners.len()];
+        let mut parents = vec![None; scanners.len()];
+        transforms[0] = Some(Transform::IDENTITY);
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(known) = queue.pop_front() {
+            let known_transform = transforms[known].unwrap();
+            for other in 0..scanners.len() {
+                if transforms[other].is_some()
+                    || fingerprints[known].shared(&fingerprints[other]) < min_shared
+                {
+                    continue;
+                }
+                if let Some(relative) = scanners[known].align(&scanners[other], self.min_overlap) {
+                    transforms[other] = Some(known_transform.compose(&relative));
+                    parents[other] = Some(known);
+                    queue.push_back(other);
+                }
+            }
+        }
+
+        let unaligned: Vec<usize> = (0..scanners.len())
+            .filter(|i| transforms[*i].is_none())
+            .collect();
+        if !unaligned.is_empty() {
+            return Err(AlignError::Unaligned(unaligned));
+        }
+
+        let transforms: Vec<Transform> = transforms.into_iter().map(Option::unwrap).collect();
+        let beacons = scanners
+            .iter()
+            .zip(transforms.iter())
+            .flat_map(|(scanner, transform)| scanner.beacons.iter().map(|p| transform.apply(p)))
+            .collect();
+
+        Ok(ScannerMap {
+            transforms,
+            parents,
+            beacons,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rotation;
+
+    fn chain(length: usize) -> (Vec<Scanner>, Vec<Transform>) {
+        let rotations = Rotation::all();
+        // scattered with a linear congruential generator, so that no two cubes match
+        let mut seed: u64 = 19;
+        let mut random = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % 1000) as i32 - 500
+        };
+        let world: Vec<Vec<Point3D>> = (0..length as i32)
+            .map(|s| {
+                (0..12)
+                    .map(|_| Point3D(1000 * s + random(), random(), random()))
+                    .collect()
+            })
+            .collect();
+
+        let mut scanners = vec![];
+        let mut placements = vec![];
+        for s in 0..length {
+            let placement = Transform {
+                rotation: rotations[(s * 7) % 24],
+                translation: Point3D(1000 * s as i32, 5 * s as i32, -3 * s as i32),
+            };
+            // the inverse of a rotation matrix is its transpose
+            let m = placement.rotation.0;
+            let inverse = Rotation([0, 1, 2].map(|i| [0, 1, 2].map(|j| m[j][i])));
+            let seen = world[s].iter().chain(if s > 0 {
+                world[s - 1].iter()
+            } else {
+                [].iter()
+            });
+            let beacons = seen
+                .map(|p| inverse.apply(&(*p - placement.translation)))
+                .collect();
+            scanners.push(Scanner { beacons });
+            placements.push(placement);
+        }
+        (scanners, placements)
+    }
+
+    #[test]
+    fn test_chain() {
+        let (scanners, placements) = chain(5);
+        let map = Aligner::new().align(&scanners).unwrap();
+        for (s, placement) in placements.iter().enumerate() {
+            assert_eq!(map.transform(s), placement);
+        }
+        assert_eq!(map.beacons().len(), 5 * 12);
+        assert_eq!(map.parent(0), None);
+        assert_eq!(map.parent(3), Some(2));
+        assert_eq!(map.path(4), [0, 1, 2, 3, 4]);
+        assert_eq!(map.max_distance(), 4000 + 20 + 12);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Aligner::new().align(&[]).unwrap_err(),
+            AlignError::NoScanners
+        );
+
+        // 12 shared beacons are not enough if 13 are required
+        let (scanners, _) = chain(3);
+        assert_eq!(
+            Aligner::new()
+                .with_min_overlap(13)
+                .unwrap()
+                .align(&scanners)
+                .unwrap_err(),
+            AlignError::Unaligned(vec![1, 2])
+        );
+
+        // cutting the chain leaves the tail out
+        let (mut scanners, _) = chain(4);
+        scanners[2].beacons.truncate(12);
+        let err = Aligner::new().align(&scanners).unwrap_err();
+        assert_eq!(err, AlignError::Unaligned(vec![2, 3]));
+        assert_eq!(
+            err.to_string(),
+            "Cannot align scanners [2, 3] with scanner 0"
+        );
+
+        for min_overlap in [0, 1, 2] {
+            assert_eq!(
+                Aligner::new().with_min_overlap(min_overlap).unwrap_err(),
+                AlignError::MinOverlapTooSmall(min_overlap)
+            );
+        }
+        assert!(Aligner::new().with_min_overlap(3).is_ok());
+
+        // two beacons along an axis line up after 8 of the 24 rotations, so aligning on
+        // them picks whichever comes first
+        let pair = Scanner {
+            beacons: vec![Point3D(0, 0, 0), Point3D(5, 0, 0)],
+        };
+        let fitting: Vec<Rotation> = Rotation::all()
+            .into_iter()
+            .filter(|rotation| {
+                let d = rotation.apply(&Point3D(5, 0, 0));
+                d == Point3D(5, 0, 0) || d == Point3D(-5, 0, 0)
+            })
+            .collect();
+        assert_eq!(fitting.len(), 8);
+        let m = fitting[7].0;
+        let inverse = Rotation([0, 1, 2].map(|i| [0, 1, 2].map(|j| m[j][i])));
+        let turned = Scanner {
+            beacons: pair.beacons.iter().map(|p| inverse.apply(p)).collect(),
+        };
+        let found = pair.align(&turned, 2).unwrap();
+        assert_ne!(found.rotation, fitting[7]);
+        assert!(turned
+            .beacons
+            .iter()
+            .all(|p| pair.beacons.contains(&found.apply(p))));
+    }
+}
@@ -1,54 +1,22 @@
+mod alignment;
 mod point3d;
+mod rotation;
 mod scanner;
-use std::collections::HashSet;
-use point3d::*;
-use scanner::*;
-
-fn process_scanners (mut unknown_scanners: Vec<Scanner>) -> Vec<Scanner> {
-    let mut first_scanner = unknown_scanners.remove(0);
-    first_scanner.position = Some(Point3D(0,0,0));
-    let mut known_scanners: Vec<Scanner> = vec![first_scanner];
-
-    while !unknown_scanners.is_empty() {
-        let known_scanner_length = known_scanners.len();
-        for i in 0..known_scanner_length {
-            for j in (0..unknown_scanners.len()).rev() {
-                if let Some(matched_scanner) = known_scanners[i].matches(&unknown_scanners[j]) {
-                    known_scanners.push(matched_scanner);
-                    unknown_scanners.remove(j);
-                }
-            }
-        }
-    }
-
-    known_scanners
-}
+pub use alignment::{AlignError, Aligner, ScannerMap, DEFAULT_MIN_OVERLAP};
+pub use point3d::*;
+pub use rotation::*;
+pub use scanner::*;
 
 pub fn part1(input: &str) -> usize {
-    let unknown_scanners = scanners_from_input(input);
-    let known_scanners = process_scanners(unknown_scanners);
-
-    let points: HashSet<Point3D> = known_scanners.iter().flat_map(|s| s.beacons.clone()).collect();
-    points.len()
+    let scanners = scanners_from_input(input);
+    let map = Aligner::new().align(&scanners).unwrap();
+    map.beacons().len()
 }
 
 pub fn part2(input: &str) -> i32 {
-    let unknown_scanners = scanners_from_input(input);
-    let known_scanners = process_scanners(unknown_scanners);
-
-    let mut max_distance = 0;
-    for s1 in &known_scanners {
-        for s2 in &known_scanners {
-            let pos1 = s1.position.unwrap();
-            let pos2 = s2.position.unwrap();
-            let distance = (pos1.0 - pos2.0).abs() + (pos1.1 - pos2.1).abs() + (pos1.2 - pos2.2).abs();
-
-            max_distance = distance.max(max_distance);
-        }
-    }
-
-
-    max_distance
+    let scanners = scanners_from_input(input);
+    let map = Aligner::new().align(&scanners).unwrap();
+    map.max_distance()
 }
 
 #[cfg(test)]
@@ -0,0 +1,89 @@
+use crate::Point3D;
+use std::ops::Mul;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation(pub [[i32; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    // signed permutation matrices with determinant 1, the other 24 are mirror images
+    pub fn all() -> Vec<Rotation> {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rotations = vec![];
+        for axes in permutations {
+            for signs in 0..8 {
+                let mut m = [[0; 3]; 3];
+                for (row, axis) in axes.iter().enumerate() {
+                    m[row][*axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                let rotation = Rotation(m);
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+        rotations
+    }
+
+    pub fn determinant(&self) -> i32 {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, p: &Point3D) -> Point3D {
+        let m = &self.0;
+        Point3D(
+            m[0][0] * p.0 + m[0][1] * p.1 + m[0][2] * p.2,
+            m[1][0] * p.0 + m[1][1] * p.1 + m[1][2] * p.2,
+            m[2][0] * p.0 + m[2][1] * p.1 + m[2][2] * p.2,
+        )
+    }
+}
+
+impl Mul for Rotation {
+    type Output = Rotation;
+
+    fn mul(self, rhs: Rotation) -> Rotation {
+        let mut m = [[0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.0[i][k] * rhs.0[k][j]).sum();
+            }
+        }
+        Rotation(m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_all_rotations() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert!(rotations.contains(&Rotation::IDENTITY));
+
+        // all different, and closed under composition
+        let p = Point3D(1, 2, 3);
+        let images: HashSet<Point3D> = rotations.iter().map(|r| r.apply(&p)).collect();
+        assert_eq!(images.len(), 24);
+        for a in rotations.iter() {
+            for b in rotations.iter() {
+                assert!(rotations.contains(&(*a * *b)));
+                assert_eq!((*a * *b).apply(&p), a.apply(&b.apply(&p)));
+            }
+        }
+    }
+}
@@ -1,10 +1,8 @@
-use crate::Point3D;
-use generator::*;
+use crate::{Point3D, Rotation};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct Scanner {
-    pub position: Option<Point3D>,
     pub beacons: Vec<Point3D>,
 }
 
@@ -18,132 +16,75 @@ pub fn scanners_from_input(input: &str) -> Vec<Scanner> {
                 .map(|x| x.parse().unwrap())
                 .collect();
 
-            Scanner {
-                position: None,
-                beacons,
-            }
+            Scanner { beacons }
         })
         .collect()
 }
 
-fn scanner_from_raw_coordinates(x: &[i32], y: &[i32], z: &[i32]) -> Scanner {
-    let beacons: Vec<Point3D> = x
-        .iter()
-        .zip(y.iter())
-        .zip(z.iter())
-        .map(|((x, y), z)| Point3D(*x, *y, *z))
-        .collect();
-
-    Scanner {
-        position: None,
-        beacons,
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Point3D,
 }
 
-impl Scanner {
-    pub fn moved(&self, point: &Point3D) -> Scanner {
-        Scanner {
-            position: Some(*point),
-            beacons: self.beacons.iter().map(|p| p + point).collect(),
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        rotation: Rotation::IDENTITY,
+        translation: Point3D(0, 0, 0),
+    };
+
+    pub fn apply(&self, p: &Point3D) -> Point3D {
+        self.rotation.apply(p) + self.translation
+    }
+
+    pub fn compose(&self, inner: &Transform) -> Transform {
+        Transform {
+            rotation: self.rotation * inner.rotation,
+            translation: self.apply(&inner.translation),
         }
     }
+}
 
-    pub fn rotations(&self) -> Generator<'_, (), Scanner> {
-        Gn::new_scoped(move |mut s| {
-            let (x, inv_x, y, inv_y, z, inv_z) = self.beacons.iter().fold(
-                // Now this is quite alot of arguments BUT, it only iterates through beacons once!
-                (vec![], vec![], vec![], vec![], vec![], vec![]),
-                |(mut x, mut xi, mut y, mut yi, mut z, mut zi), p| {
-                    x.push(p.0);
-                    xi.push(-p.0);
-                    y.push(p.1);
-                    yi.push(-p.1);
-                    z.push(p.2);
-                    zi.push(-p.2);
-
-                    (x, xi, y, yi, z, zi)
-                },
-            );
-
-            s.yield_with(self.clone());
-            s.yield_with(scanner_from_raw_coordinates(&x, &z, &y));
-            s.yield_with(scanner_from_raw_coordinates(&y, &x, &z));
-            s.yield_with(scanner_from_raw_coordinates(&y, &z, &x));
-            s.yield_with(scanner_from_raw_coordinates(&z, &x, &y));
-            s.yield_with(scanner_from_raw_coordinates(&z, &y, &x));
-
-            // inverting z only
-            s.yield_with(scanner_from_raw_coordinates(&x, &y, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&x, &inv_z, &y));
-            s.yield_with(scanner_from_raw_coordinates(&y, &x, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&y, &inv_z, &x));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &x, &y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &y, &x));
-
-            // inverting y only
-            s.yield_with(scanner_from_raw_coordinates(&x, &inv_y, &z));
-            s.yield_with(scanner_from_raw_coordinates(&x, &z, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &x, &z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &z, &x));
-            s.yield_with(scanner_from_raw_coordinates(&z, &x, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&z, &inv_y, &x));
-
-            // inverted z and y
-            s.yield_with(scanner_from_raw_coordinates(&x, &inv_y, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&x, &inv_z, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &x, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &inv_z, &x));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &x, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &inv_y, &x));
-
-            // inverted x, z & y
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &inv_y, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &inv_z, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &inv_x, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &inv_z, &inv_x));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &inv_x, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &inv_y, &inv_x));
-
-            // inverted x & y
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &inv_y, &z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &z, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &inv_x, &z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_y, &z, &inv_x));
-            s.yield_with(scanner_from_raw_coordinates(&z, &inv_x, &inv_y));
-            s.yield_with(scanner_from_raw_coordinates(&z, &inv_y, &inv_x));
-
-            // inverted x & z
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &y, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &inv_z, &y));
-            s.yield_with(scanner_from_raw_coordinates(&y, &inv_x, &inv_z));
-            s.yield_with(scanner_from_raw_coordinates(&y, &inv_z, &inv_x));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &inv_x, &y));
-            s.yield_with(scanner_from_raw_coordinates(&inv_z, &y, &inv_x));
-
-            // inverted x
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &y, &z));
-            s.yield_with(scanner_from_raw_coordinates(&inv_x, &z, &y));
-            s.yield_with(scanner_from_raw_coordinates(&y, &inv_x, &z));
-            s.yield_with(scanner_from_raw_coordinates(&y, &z, &inv_x));
-            s.yield_with(scanner_from_raw_coordinates(&z, &inv_x, &y));
-            s.yield_with(scanner_from_raw_coordinates(&z, &y, &inv_x));
-
-            done!();
-        })
+#[derive(Debug, Clone, Default)]
+pub struct Fingerprint(HashMap<i64, usize>);
+
+impl Fingerprint {
+    pub fn shared(&self, other: &Fingerprint) -> usize {
+        self.0
+            .iter()
+            .map(|(distance, count)| (*count).min(*other.0.get(distance).unwrap_or(&0)))
+            .sum()
     }
+}
 
-    pub fn matches(&self, scanner: &Scanner) -> Option<Scanner> {
-        for rotated_scanner in scanner.rotations() {
-            let mut matching: HashMap<Point3D, usize> = Default::default();
+impl Scanner {
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut distances = HashMap::new();
+        for (i, a) in self.beacons.iter().enumerate() {
+            for b in self.beacons[i + 1..].iter() {
+                let d = *a - *b;
+                let squared = [d.0, d.1, d.2].iter().map(|c| (*c as i64).pow(2)).sum();
+                *distances.entry(squared).or_insert(0) += 1;
+            }
+        }
+        Fingerprint(distances)
+    }
+
+    pub fn align(&self, other: &Scanner, min_overlap: usize) -> Option<Transform> {
+        for rotation in Rotation::all() {
+            let rotated: Vec<Point3D> = other.beacons.iter().map(|p| rotation.apply(p)).collect();
+            let mut offsets: HashMap<Point3D, usize> = HashMap::new();
             for point0 in &self.beacons {
-                for point1 in &rotated_scanner.beacons {
-                    let key = point0 - point1;
-                    let x = matching.entry(key).or_default();
-                    *x += 1;
-
-                    if *x >= 12 {
-                        let normalised_scanner = rotated_scanner.moved(&key);
-                        return Some(normalised_scanner);
+                for point1 in &rotated {
+                    let offset = point0 - point1;
+                    let votes = offsets.entry(offset).or_default();
+                    *votes += 1;
+
+                    if *votes >= min_overlap {
+                        return Some(Transform {
+                            rotation,
+                            translation: offset,
+                        });
                     }
                 }
             }
@@ -158,9 +99,7 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_matching() {
-        let input = "--- scanner 0 ---
+    const EXAMPLE: &str = "--- scanner 0 ---
 404,-588,-901
 528,-643,409
 -838,591,734
@@ -214,12 +153,38 @@ mod tests {
 755,-354,-619
 553,889,-390";
 
-        let scanners = scanners_from_input(input);
+    #[test]
+    fn test_matching() {
+        let scanners = scanners_from_input(EXAMPLE);
 
         let first = &scanners[0];
         let second = &scanners[1];
 
-        let matching = first.matches(second).expect("It should match");
-        assert_eq!(matching.position.unwrap(), Point3D(68, -1246, -43));
+        // 12 beacons in common, so at least 66 pairs of them
+        assert!(first.fingerprint().shared(&second.fingerprint()) >= 66);
+
+        let transform = first.align(second, 12).expect("It should match");
+        assert_eq!(transform.translation, Point3D(68, -1246, -43));
+        assert_eq!(
+            transform.apply(&Point3D(686, 422, 578)),
+            Point3D(-618, -824, -621)
+        );
+        assert_eq!(first.align(second, 13), None);
+    }
+
+    #[test]
+    fn test_compose() {
+        let rotations = Rotation::all();
+        let a = Transform {
+            rotation: rotations[5],
+            translation: Point3D(1, 2, 3),
+        };
+        let b = Transform {
+            rotation: rotations[17],
+            translation: Point3D(-7, 0, 11),
+        };
+        let p = Point3D(5, -6, 7);
+        assert_eq!(a.compose(&b).apply(&p), a.apply(&b.apply(&p)));
+        assert_eq!(Transform::IDENTITY.compose(&a), a);
     }
 }
@@ -1,21 +1,81 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    #[error("Positions start at 1, found {0}")]
+    InvalidPosition(usize),
+    #[error("The number of universes does not fit in a u128")]
+    Overflow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub board_size: usize,
+    pub die_faces: usize,
+    pub rolls_per_turn: usize,
+    pub winning_score: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            board_size: 10,
+            die_faces: 3,
+            rolls_per_turn: 3,
+            winning_score: 21,
+        }
+    }
+}
+
+impl Rules {
+    pub fn practice() -> Self {
+        Rules {
+            die_faces: 100,
+            winning_score: 1000,
+            ..Default::default()
+        }
+    }
+
+    fn roll_totals(&self) -> Result<Vec<(usize, u128)>, GameError> {
+        let mut totals: Vec<u128> = vec![1];
+        for _ in 0..self.rolls_per_turn {
+            let mut next = vec![0_u128; totals.len() + self.die_faces];
+            for (total, count) in totals.iter().enumerate() {
+                for face in 1..=self.die_faces {
+                    next[total + face] = next[total + face]
+                        .checked_add(*count)
+                        .ok_or(GameError::Overflow)?;
+                }
+            }
+            totals = next;
+        }
+        Ok(totals
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .collect())
+    }
+}
 
 #[derive(Debug)]
 struct DeterministicDie {
+    faces: usize,
     current: usize,
     num_rolls: usize,
 }
 
 impl DeterministicDie {
-    fn new() -> Self {
+    fn new(faces: usize) -> Self {
         DeterministicDie {
+            faces,
             current: 0,
             num_rolls: 0,
         }
     }
 
     fn roll(&mut self) -> usize {
-        if self.current == 100 {
+        if self.current == self.faces {
             self.current = 1;
         } else {
             self.current += 1;
@@ -24,153 +84,150 @@ impl DeterministicDie {
         self.num_rolls += 1;
         self.current
     }
-
-    fn roll3(&mut self) -> (usize, usize, usize) {
-        (self.roll(), self.roll(), self.roll())
-    }
 }
 
-#[derive(Debug, Clone)]
-struct Player {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Player {
     pos: usize,
     score: usize,
 }
 
 impl Player {
-    fn new(pos: usize) -> Self {
-        Player { pos, score: 0 }
+    pub fn new(pos: usize) -> Result<Self, GameError> {
+        if pos == 0 {
+            return Err(GameError::InvalidPosition(pos));
+        }
+        Ok(Player {
+            pos: pos - 1,
+            score: 0,
+        })
     }
 
-    fn play(&mut self, die: &mut DeterministicDie) {
-        let rolls = die.roll3();
-        let tot = rolls.0 + rolls.1 + rolls.2;
-        let new_pos = (self.pos + tot) % 10;
-        self.score += new_pos + 1;
-        self.pos = new_pos;
+    pub fn score(&self) -> usize {
+        self.score
     }
 
-    fn play_with_roll(&mut self, roll: usize) {
-        let new_pos = (self.pos + roll) % 10;
+    fn play_with_roll(&mut self, roll: usize, board_size: usize) {
+        let new_pos = (self.pos + roll) % board_size;
         self.score += new_pos + 1;
         self.pos = new_pos;
     }
-
-    fn has_won_part1(&self) -> bool {
-        self.score >= 1000
-    }
-
-    fn has_won_part2(&self) -> bool {
-        self.score >= 21
-    }
 }
 
 impl FromStr for Player {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pos: usize = s.split(": ").nth(1).unwrap().parse().unwrap();
-        Ok(Player::new(pos - 1))
+        let pos: usize = s.split(": ").nth(1).ok_or(())?.parse().map_err(|_| ())?;
+        Player::new(pos).map_err(|_| ())
     }
 }
 
-#[derive(Debug, Clone)]
-struct Game {
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WinDistribution {
+    pub by_move: Vec<[u128; 2]>,
+}
+
+impl WinDistribution {
+    pub fn wins(&self) -> [u128; 2] {
+        self.by_move
+            .iter()
+            .fold([0, 0], |acc, wins| [acc[0] + wins[0], acc[1] + wins[1]])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Game {
     players: [Player; 2],
     current_player: usize,
 }
 
+impl FromStr for Game {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut l = s.lines();
+        let player1 = l.next().ok_or(())?.parse()?;
+        let player2 = l.next().ok_or(())?.parse()?;
+        Ok(Game::new(player1, player2))
+    }
+}
+
 impl Game {
-    fn new(player1: Player, player2: Player) -> Self {
+    pub fn new(player1: Player, player2: Player) -> Self {
         Game {
             players: [player1, player2],
             current_player: 0,
         }
     }
 
-    fn play_turn(&mut self, die: usize) {
+    pub fn players(&self) -> &[Player; 2] {
+        &self.players
+    }
+
+    fn play_turn(&mut self, roll: usize, rules: &Rules) -> bool {
         let player = &mut self.players[self.current_player];
-        player.play_with_roll(die);
+        player.play_with_roll(roll, rules.board_size);
+        let won = player.score >= rules.winning_score;
         self.current_player = (self.current_player + 1) % self.players.len();
+        won
     }
-}
 
-pub fn part1(input: &str) -> usize {
-    let mut l = input.lines();
-    let mut player1: Player = l.next().unwrap().parse().unwrap();
-    let mut player2: Player = l.next().unwrap().parse().unwrap();
-
-    let mut current_player = &mut player1;
-    let mut other_player = &mut player2;
-    let mut die = DeterministicDie::new();
-
-    loop {
-        current_player.play(&mut die);
-        if current_player.has_won_part1() {
-            return die.num_rolls * other_player.score;
-        }
-        std::mem::swap(&mut current_player, &mut other_player);
-    }
-}
-
-pub fn part2(input: &str) -> usize {
-    let mut l = input.lines();
-    let (player1, player2) = (
-        l.next().unwrap().parse().unwrap(),
-        l.next().unwrap().parse().unwrap(),
-    );
-
-    let game = Game::new(player1, player2);
-
-    let mut victories = [0_usize, 0_usize];
-    let mut games: Vec<(Game, usize)> = vec![(game, 1)];
-
-    // At every turn, every time we roll a die we split the universe in 3.
-    // Every universe will represent a different outcome of rolling the die (1, 2, or 3).
-    // Because we have to roll the die 3 times, we end up with 27 new universes after every
-    // turn.
-    // The total of a turn is the sum of the 3 dice. This means that the total can vary between
-    // 3 (worst score) to 9 (best score).
-    // The following table encodes all the possible scores (3..9) and how many chances do they have
-    // to happen (3 & 9 only once, 6 will happen 7 times per turn).
-    // By doing this we can reduce the number of simulation to do, because we don't need
-    // to run all the 27 simulations (per turn) but we can just run the 7 different cases.
-    // We will then have to multiply the outcome of every case to how many times that case will repeat.
-    let universes = [
-        (3_usize, 1_usize),
-        (4, 3),
-        (5, 6),
-        (6, 7),
-        (7, 6),
-        (8, 3),
-        (9, 1),
-    ];
-
-    while !games.is_empty() {
-        let (current_game, weight) = games.pop().unwrap();
-
-        // if the game is over accumulate the victories for the winning player
-        let mut is_over = false;
-        for (i, victory) in victories.iter_mut().enumerate() {
-            if current_game.players[i].has_won_part2() {
-                *victory += weight;
-                is_over = true;
-                break;
+    pub fn play_deterministic(&mut self, rules: &Rules) -> usize {
+        let mut die = DeterministicDie::new(rules.die_faces);
+        loop {
+            let roll = (0..rules.rolls_per_turn).map(|_| die.roll()).sum();
+            if self.play_turn(roll, rules) {
+                return die.num_rolls;
             }
         }
-        if is_over {
-            continue;
-        }
-
-        // if the current game is not over, keep splitting it
-        for (roll, new_weight) in universes.iter() {
-            let mut new_game = current_game.clone();
-            new_game.play_turn(*roll);
+    }
 
-            games.push((new_game, weight * new_weight));
+    // universes reaching the same state are merged and carried forward together
+    pub fn play_dirac(&self, rules: &Rules) -> Result<WinDistribution, GameError> {
+        let totals = rules.roll_totals()?;
+        let mut distribution = WinDistribution::default();
+        let mut universes: HashMap<Game, u128> = HashMap::from([(*self, 1)]);
+        // the overall wins are tracked too, so that `wins()` cannot overflow either
+        let mut all_wins = [0_u128, 0];
+
+        while !universes.is_empty() {
+            let mut wins = [0, 0];
+            let mut next: HashMap<Game, u128> = HashMap::new();
+            for (game, count) in universes {
+                for (total, ways) in totals.iter() {
+                    let new_count = count.checked_mul(*ways).ok_or(GameError::Overflow)?;
+                    let mut new_game = game;
+                    let slot = if new_game.play_turn(*total, rules) {
+                        &mut wins[game.current_player]
+                    } else {
+                        next.entry(new_game).or_insert(0)
+                    };
+                    *slot = slot.checked_add(new_count).ok_or(GameError::Overflow)?;
+                }
+            }
+            for (all, new) in all_wins.iter_mut().zip(wins) {
+                *all = all.checked_add(new).ok_or(GameError::Overflow)?;
+            }
+            distribution.by_move.push(wins);
+            universes = next;
         }
+
+        Ok(distribution)
     }
+}
 
-    *victories.iter().max().unwrap()
+pub fn part1(input: &str) -> usize {
+    let mut game: Game = input.parse().unwrap();
+    let num_rolls = game.play_deterministic(&Rules::practice());
+    let loser_score = game.players().iter().map(Player::score).min().unwrap();
+    num_rolls * loser_score
+}
+
+pub fn part2(input: &str) -> u128 {
+    let game: Game = input.parse().unwrap();
+    let wins = game.play_dirac(&Rules::default()).unwrap().wins();
+    wins[0].max(wins[1])
 }
 
 #[cfg(test)]
@@ -184,6 +241,92 @@ mod tests {
 Player 2 starting position: 8";
 
         assert_eq!(part1(input), 739785);
+        assert_eq!(part2(input), 444356092776315);
+
+        let wins = input
+            .parse::<Game>()
+            .unwrap()
+            .play_dirac(&Rules::default())
+            .unwrap()
+            .wins();
+        assert_eq!(wins, [444356092776315, 341960390180808]);
+    }
+
+    #[test]
+    fn test_roll_totals() {
+        assert_eq!(
+            Rules::default().roll_totals().unwrap(),
+            [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+        );
+        let coin = Rules {
+            die_faces: 2,
+            rolls_per_turn: 2,
+            ..Default::default()
+        };
+        assert_eq!(coin.roll_totals().unwrap(), [(2, 1), (3, 2), (4, 1)]);
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        // a 1-sided die is deterministic: both players always move by 1
+        let rules = Rules {
+            board_size: 4,
+            die_faces: 1,
+            rolls_per_turn: 1,
+            winning_score: 10,
+        };
+        let game = Game::new(Player::new(1).unwrap(), Player::new(1).unwrap());
+        let distribution = game.play_dirac(&rules).unwrap();
+        // 2, 3, 4 and 1: the first player gets to 10 on their 4th turn, the 7th move
+        assert_eq!(distribution.wins(), [1, 0]);
+        assert_eq!(distribution.by_move.len(), 7);
+        assert_eq!(distribution.by_move[6], [1, 0]);
+
+        let mut deterministic = game;
+        assert_eq!(deterministic.play_deterministic(&rules), 7);
+        assert_eq!(deterministic.players()[1].score(), 9);
+
+        // a bigger die and board still adds up all the universes
+        let rules = Rules {
+            board_size: 12,
+            die_faces: 4,
+            rolls_per_turn: 2,
+            winning_score: 30,
+        };
+        let distribution = game.play_dirac(&rules).unwrap();
+        let total: u128 = distribution
+            .by_move
+            .iter()
+            .enumerate()
+            .map(|(i, wins)| {
+                (wins[0] + wins[1]) * 16_u128.pow((distribution.by_move.len() - i - 1) as u32)
+            })
+            .sum();
+        assert_eq!(total, 16_u128.pow(distribution.by_move.len() as u32));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Player::new(0), Err(GameError::InvalidPosition(0)));
+        assert_eq!("Player 1 starting position: 0".parse::<Player>(), Err(()));
+
+        // C(140, 70) ways to roll a total of 210 with 140 coins
+        let rules = Rules {
+            die_faces: 2,
+            rolls_per_turn: 140,
+            ..Default::default()
+        };
+        assert_eq!(rules.roll_totals(), Err(GameError::Overflow));
+
+        // every turn multiplies the universes by up to C(64, 32), about 2^61
+        let rules = Rules {
+            die_faces: 2,
+            rolls_per_turn: 64,
+            winning_score: 1000,
+            ..Default::default()
+        };
+        let game = Game::new(Player::new(1).unwrap(), Player::new(1).unwrap());
+        assert_eq!(game.play_dirac(&rules), Err(GameError::Overflow));
     }
 
     #[test]
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<const D: usize> {
+    min: [i64; D],
+    max: [i64; D],
+}
+
+impl<const D: usize> Cuboid<D> {
+    pub fn new(min: [i64; D], max: [i64; D]) -> Self {
+        Cuboid { min, max }
+    }
+
+    pub fn from_inclusive(ranges: [(i64, i64); D]) -> Self {
+        Cuboid {
+            min: ranges.map(|(from, _)| from),
+            max: ranges.map(|(_, to)| to + 1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..D).any(|i| self.min[i] >= self.max[i])
+    }
+
+    pub fn volume(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..D)
+            .map(|i| (self.max[i] - self.min[i]) as u128)
+            .product()
+    }
+
+    pub fn contains(&self, point: &[i64; D]) -> bool {
+        (0..D).all(|i| self.min[i] <= point[i] && point[i] < self.max[i])
+    }
+
+    pub fn intersection(&self, other: &Cuboid<D>) -> Option<Cuboid<D>> {
+        let common = Cuboid {
+            min: std::array::from_fn(|i| self.min[i].max(other.min[i])),
+            max: std::array::from_fn(|i| self.max[i].min(other.max[i])),
+        };
+        if common.is_empty() {
+            None
+        } else {
+            Some(common)
+        }
+    }
+}
+
+// inclusion–exclusion: the weights of the cuboids holding a point add up to 1 or 0
+#[derive(Debug, Clone, Default)]
+pub struct CuboidSet<const D: usize> {
+    terms: HashMap<Cuboid<D>, i64>,
+}
+
+impl<const D: usize> CuboidSet<D> {
+    pub fn new() -> Self {
+        CuboidSet {
+            terms: HashMap::new(),
+        }
+    }
+
+    pub fn terms(&self) -> usize {
+        self.terms.len()
+    }
+
+    fn add(&mut self, cuboid: Cuboid<D>, weight: i64) {
+        let total = self.terms.entry(cuboid).or_insert(0);
+        *total += weight;
+        if *total == 0 {
+            self.terms.remove(&cuboid);
+        }
+    }
+
+    fn overlaps(&self, cuboid: &Cuboid<D>) -> Vec<(Cuboid<D>, i64)> {
+        self.terms
+            .iter()
+            .filter_map(|(term, weight)| term.intersection(cuboid).map(|c| (c, *weight)))
+            .collect()
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid<D>) {
+        self.remove(cuboid);
+        if !cuboid.is_empty() {
+            self.add(cuboid, 1);
+        }
+    }
+
+    pub fn remove(&mut self, cuboid: Cuboid<D>) {
+        for (overlap, weight) in self.overlaps(&cuboid) {
+            self.add(overlap, -weight);
+        }
+    }
+
+    pub fn intersection(&self, other: &CuboidSet<D>) -> CuboidSet<D> {
+        let mut result = CuboidSet::new();
+        for (term, weight) in other.terms.iter() {
+            for (overlap, overlap_weight) in self.overlaps(term) {
+                result.add(overlap, weight * overlap_weight);
+            }
+        }
+        result
+    }
+
+    pub fn union(&self, other: &CuboidSet<D>) -> CuboidSet<D> {
+        let mut result = self.difference(other);
+        for (term, weight) in other.terms.iter() {
+            result.add(*term, *weight);
+        }
+        result
+    }
+
+    pub fn difference(&self, other: &CuboidSet<D>) -> CuboidSet<D> {
+        let mut result = self.clone();
+        for (term, weight) in self.intersection(other).terms {
+            result.add(term, -weight);
+        }
+        result
+    }
+
+    pub fn volume(&self) -> u128 {
+        let signed: i128 = self
+            .terms
+            .iter()
+            .map(|(term, weight)| term.volume() as i128 * *weight as i128)
+            .sum();
+        signed as u128
+    }
+
+    pub fn volume_in(&self, region: &Cuboid<D>) -> u128 {
+        let signed: i128 = self
+            .overlaps(region)
+            .iter()
+            .map(|(overlap, weight)| overlap.volume() as i128 * *weight as i128)
+            .sum();
+        signed as u128
+    }
+
+    pub fn contains(&self, point: &[i64; D]) -> bool {
+        let weight: i64 = self
+            .terms
+            .iter()
+            .filter(|(term, _)| term.contains(point))
+            .map(|(_, weight)| weight)
+            .sum();
+        weight > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(from: i64, to: i64) -> Cuboid<3> {
+        Cuboid::from_inclusive([(from, to); 3])
+    }
+
+    #[test]
+    fn test_cuboid() {
+        assert_eq!(cube(10, 12).volume(), 27);
+        assert_eq!(cube(10, 9).volume(), 0);
+        assert!(cube(10, 12).contains(&[12, 10, 11]));
+        assert!(!cube(10, 12).contains(&[13, 10, 11]));
+        assert_eq!(cube(10, 12).intersection(&cube(12, 14)), Some(cube(12, 12)));
+        assert_eq!(cube(10, 12).intersection(&cube(13, 14)), None);
+    }
+
+    #[test]
+    fn test_example() {
+        let mut set = CuboidSet::new();
+        set.insert(cube(10, 12));
+        assert_eq!(set.volume(), 27);
+        set.insert(cube(11, 13));
+        assert_eq!(set.volume(), 27 + 19);
+        set.remove(cube(9, 11));
+        assert_eq!(set.volume(), 27 + 19 - 8);
+        set.insert(cube(10, 10));
+        assert_eq!(set.volume(), 39);
+
+        assert!(set.contains(&[10, 10, 10]));
+        assert!(!set.contains(&[11, 11, 11]));
+        assert!(set.contains(&[13, 13, 13]));
+        assert_eq!(set.volume_in(&cube(12, 13)), 8);
+        assert_eq!(set.volume_in(&cube(100, 200)), 0);
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        // two 1D segments: [0, 10) and [5, 15)
+        let mut a = CuboidSet::new();
+        a.insert(Cuboid::new([0], [10]));
+        let mut b = CuboidSet::new();
+        b.insert(Cuboid::new([5], [15]));
+
+        assert_eq!(a.union(&b).volume(), 15);
+        assert_eq!(a.intersection(&b).volume(), 5);
+        assert_eq!(a.difference(&b).volume(), 5);
+        assert_eq!(b.difference(&a).volume(), 5);
+        assert!(a.difference(&b).contains(&[4]));
+        assert!(!a.difference(&b).contains(&[5]));
+
+        // removing everything leaves no terms behind
+        let mut c = a.union(&b);
+        c.remove(Cuboid::new([-100], [100]));
+        assert_eq!(c.volume(), 0);
+        assert_eq!(c.terms(), 0);
+    }
+
+    #[test]
+    fn test_higher_dimensions() {
+        let mut set: CuboidSet<4> = CuboidSet::new();
+        set.insert(Cuboid::new([0; 4], [2; 4]));
+        set.insert(Cuboid::new([1; 4], [3; 4]));
+        assert_eq!(set.volume(), 16 + 16 - 1);
+        set.remove(Cuboid::new([0, 0, 0, 0], [3, 3, 3, 1]));
+        // drops the half of the first cuboid with the last coordinate at 0
+        assert_eq!(set.volume(), 16 + 16 - 1 - 8);
+        assert!(set.contains(&[2, 2, 2, 2]));
+        assert!(!set.contains(&[2, 2, 2, 0]));
+    }
+}
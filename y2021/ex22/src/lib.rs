@@ -1,31 +1,17 @@
-use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
-};
+use std::str::FromStr;
 
-pub mod rectangle;
-use rectangle::*;
+mod cuboid;
+pub use cuboid::{Cuboid, CuboidSet};
 
 #[derive(Debug)]
 struct Command {
     on: bool,
-    x_range: (isize, isize),
-    y_range: (isize, isize),
-    z_range: (isize, isize),
+    cuboid: Cuboid<3>,
 }
 
-impl Command {
-    fn in_cube(&self, max: isize) -> bool {
-        let min = -max;
-
-        self.x_range.0 >= min
-            && self.x_range.1 <= max
-            && self.y_range.0 >= min
-            && self.y_range.1 <= max
-            && self.z_range.0 >= min
-            && self.z_range.1 <= max
-    }
+fn parse_range(raw: &str) -> (i64, i64) {
+    let (from, to) = raw[2..].split_once("..").unwrap();
+    (from.parse().unwrap(), to.parse().unwrap())
 }
 
 impl FromStr for Command {
@@ -34,102 +20,39 @@ impl FromStr for Command {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (on_raw, ranges_raw) = s.split_once(' ').unwrap();
         let on = on_raw == "on";
-        let mut range_parts = ranges_raw.split(',');
-        let (x_range_raw, y_range_raw, z_range_raw) = (
+        let mut range_parts = ranges_raw.split(',').map(parse_range);
+        let ranges = [
             range_parts.next().unwrap(),
             range_parts.next().unwrap(),
             range_parts.next().unwrap(),
-        );
-        let mut x_range_iter = x_range_raw[2..]
-            .split("..")
-            .map(|x| x.parse::<isize>().unwrap())
-            .take(2);
-        let x_range = (x_range_iter.next().unwrap(), x_range_iter.next().unwrap());
-        let mut y_range_iter = y_range_raw[2..]
-            .split("..")
-            .map(|y| y.parse::<isize>().unwrap())
-            .take(2);
-        let y_range = (y_range_iter.next().unwrap(), y_range_iter.next().unwrap());
-        let mut z_range_iter = z_range_raw[2..]
-            .split("..")
-            .map(|z| z.parse::<isize>().unwrap())
-            .take(2);
-        let z_range = (z_range_iter.next().unwrap(), z_range_iter.next().unwrap());
+        ];
 
         Ok(Command {
             on,
-            x_range,
-            y_range,
-            z_range,
+            cuboid: Cuboid::from_inclusive(ranges),
         })
     }
 }
 
-fn solve(commands: Vec<Command>) -> usize {
-    let mut z_ranges: HashSet<isize> = Default::default();
-    for command in commands.iter() {
-        z_ranges.insert(command.z_range.0);
-        z_ranges.insert(command.z_range.1 + 1);
-    }
-    let mut z_ranges_sorted: Vec<isize> = z_ranges.into_iter().collect();
-    z_ranges_sorted.sort();
-
-    let mut z_tickness: HashMap<isize, usize> = Default::default();
-
-    for (a, b) in z_ranges_sorted.iter().tuple_windows() {
-        z_tickness.insert(*a, (b - a) as usize);
-    }
-
-    let mut planes: HashMap<isize, RectangleList> =
-        HashMap::from_iter(z_tickness.keys().map(|z| (*z, RectangleList::new())));
-
-    for command in commands.iter() {
-        for z in z_ranges_sorted.iter() {
-            if command.z_range.0 <= *z && command.z_range.1 >= *z {
-                let rectangles = planes.get(z).unwrap();
-                let rectangle_to_subtract = Rectangle::new(
-                    command.x_range.0,
-                    command.y_range.0,
-                    command.x_range.1 + 1,
-                    command.y_range.1 + 1,
-                );
-                let mut diffed = diff(rectangles, &rectangle_to_subtract);
-                if command.on {
-                    diffed.push(rectangle_to_subtract);
-                }
-                planes.insert(*z, diffed);
-            }
+fn reboot(input: &str) -> CuboidSet<3> {
+    let mut reactor = CuboidSet::new();
+    for command in input.lines().map(|l| l.parse::<Command>().unwrap()) {
+        if command.on {
+            reactor.insert(command.cuboid);
+        } else {
+            reactor.remove(command.cuboid);
         }
     }
-
-    let mut num_cubes = 0;
-
-    for (z, rectangles) in planes {
-        let tickness = z_tickness.get(&z).unwrap();
-        for rectangle in rectangles {
-            num_cubes += rectangle.area() * tickness;
-        }
-    }
-
-    num_cubes
+    reactor
 }
 
-pub fn part1(input: &str) -> usize {
-    let commands: Vec<Command> = input
-        .lines()
-        .map(|l| l.parse::<Command>().unwrap())
-        .filter(|c| c.in_cube(50))
-        .collect();
-
-    solve(commands)
+pub fn part1(input: &str) -> u128 {
+    let initialization_area = Cuboid::from_inclusive([(-50, 50); 3]);
+    reboot(input).volume_in(&initialization_area)
 }
 
-pub fn part2(input: &str) -> usize {
-    let commands: Vec<Command> = input
-        .lines()
-        .map(|l| l.parse::<Command>().unwrap())
-        .collect();
-    solve(commands)
+pub fn part2(input: &str) -> u128 {
+    reboot(input).volume()
 }
 
 #[cfg(test)]
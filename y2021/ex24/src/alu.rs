@@ -0,0 +1,328 @@
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Value(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("Line {line}: unknown instruction '{name}'")]
+    UnknownInstruction { line: usize, name: String },
+    #[error("Line {line}: invalid register '{found}'")]
+    InvalidRegister { line: usize, found: String },
+    #[error("Line {line}: invalid operand '{found}'")]
+    InvalidOperand { line: usize, found: String },
+    #[error("Line {line}: '{name}' takes {expected} operands, found {found}")]
+    OperandCount {
+        line: usize,
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RunError {
+    #[error("Instruction {0} reads past the end of the input")]
+    MissingInput(usize),
+    #[error("Instruction {0} divides by zero")]
+    DivisionByZero(usize),
+    #[error("Instruction {0} takes the modulo of a negative number or by a non-positive one")]
+    InvalidModulo(usize),
+}
+
+fn parse_register(s: &str, line: usize) -> Result<Register, ParseError> {
+    match s {
+        "w" => Ok(Register::W),
+        "x" => Ok(Register::X),
+        "y" => Ok(Register::Y),
+        "z" => Ok(Register::Z),
+        _ => Err(ParseError::InvalidRegister {
+            line,
+            found: s.to_string(),
+        }),
+    }
+}
+
+fn parse_operand(s: &str, line: usize) -> Result<Operand, ParseError> {
+    if let Ok(register) = parse_register(s, line) {
+        return Ok(Operand::Register(register));
+    }
+    s.parse()
+        .map(Operand::Value)
+        .map_err(|_| ParseError::InvalidOperand {
+            line,
+            found: s.to_string(),
+        })
+}
+
+impl Instruction {
+    pub fn parse(s: &str, line: usize) -> Result<Self, ParseError> {
+        let mut parts = s.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let operands: Vec<&str> = parts.collect();
+        let expected = match name {
+            "inp" => 1,
+            "add" | "mul" | "div" | "mod" | "eql" => 2,
+            _ => {
+                return Err(ParseError::UnknownInstruction {
+                    line,
+                    name: name.to_string(),
+                })
+            }
+        };
+        if operands.len() != expected {
+            return Err(ParseError::OperandCount {
+                line,
+                name: name.to_string(),
+                expected,
+                found: operands.len(),
+            });
+        }
+
+        let a = parse_register(operands[0], line)?;
+        if name == "inp" {
+            return Ok(Instruction::Inp(a));
+        }
+        let b = parse_operand(operands[1], line)?;
+        Ok(match name {
+            "add" => Instruction::Add(a, b),
+            "mul" => Instruction::Mul(a, b),
+            "div" => Instruction::Div(a, b),
+            "mod" => Instruction::Mod(a, b),
+            _ => Instruction::Eql(a, b),
+        })
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Register::W => "w",
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{register}"),
+            Operand::Value(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Inp(a) => write!(f, "inp {a}"),
+            Instruction::Add(a, b) => write!(f, "add {a} {b}"),
+            Instruction::Mul(a, b) => write!(f, "mul {a} {b}"),
+            Instruction::Div(a, b) => write!(f, "div {a} {b}"),
+            Instruction::Mod(a, b) => write!(f, "mod {a} {b}"),
+            Instruction::Eql(a, b) => write!(f, "eql {a} {b}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    instructions: Vec<Instruction>,
+}
+
+impl FromStr for Program {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let instructions = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| Instruction::parse(line, i + 1))
+            .collect::<Result<_, _>>()?;
+        Ok(Program { instructions })
+    }
+}
+
+impl Program {
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.instructions
+            .iter()
+            .filter(|i| matches!(i, Instruction::Inp(_)))
+            .count()
+    }
+
+    pub fn run(&self, input: &[i64]) -> Result<Alu, RunError> {
+        let mut alu = Alu::default();
+        let mut input = input.iter().copied();
+        for index in 0..self.instructions.len() {
+            alu.execute(self, index, &mut input)?;
+        }
+        Ok(alu)
+    }
+
+    pub fn accepts(&self, model_number: u64) -> bool {
+        let digits: Vec<i64> = model_number
+            .to_string()
+            .chars()
+            .map(|c| c.to_digit(10).unwrap() as i64)
+            .collect();
+        if digits.len() != self.inputs() || digits.contains(&0) {
+            return false;
+        }
+        matches!(self.run(&digits), Ok(alu) if alu.get(Register::Z) == 0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Alu {
+    pub registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register.index()]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Value(value) => value,
+        }
+    }
+
+    pub fn execute(
+        &mut self,
+        program: &Program,
+        index: usize,
+        input: &mut impl Iterator<Item = i64>,
+    ) -> Result<(), RunError> {
+        let (a, result) = match program.instructions[index] {
+            Instruction::Inp(a) => (a, input.next().ok_or(RunError::MissingInput(index))?),
+            Instruction::Add(a, b) => (a, self.get(a).wrapping_add(self.value(b))),
+            Instruction::Mul(a, b) => (a, self.get(a).wrapping_mul(self.value(b))),
+            Instruction::Div(a, b) => match self.value(b) {
+                0 => return Err(RunError::DivisionByZero(index)),
+                b => (a, self.get(a).wrapping_div(b)),
+            },
+            Instruction::Mod(a, b) => match (self.get(a), self.value(b)) {
+                (a_value, b) if a_value >= 0 && b > 0 => (a, a_value % b),
+                _ => return Err(RunError::InvalidModulo(index)),
+            },
+            Instruction::Eql(a, b) => (a, i64::from(self.get(a) == self.value(b))),
+        };
+        self.registers[a.index()] = result;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        let negate: Program = "inp x\nmul x -1".parse().unwrap();
+        assert_eq!(negate.run(&[7]).unwrap().get(Register::X), -7);
+
+        let three_times: Program = "inp z\ninp x\nmul z 3\neql z x".parse().unwrap();
+        assert_eq!(three_times.run(&[3, 9]).unwrap().get(Register::Z), 1);
+        assert_eq!(three_times.run(&[3, 8]).unwrap().get(Register::Z), 0);
+
+        let binary: Program = "inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2"
+            .parse()
+            .unwrap();
+        assert_eq!(binary.run(&[13]).unwrap().registers, [1, 1, 0, 1]);
+        assert_eq!(binary.inputs(), 1);
+        assert_eq!(binary.instructions()[2].to_string(), "mod z 2");
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let program: Program = "inp x\ndiv y x\nmod x y".parse().unwrap();
+        assert_eq!(program.run(&[]), Err(RunError::MissingInput(0)));
+        assert_eq!(program.run(&[0]), Err(RunError::DivisionByZero(1)));
+        assert_eq!(program.run(&[-3]), Err(RunError::InvalidModulo(2)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| s.parse::<Program>().unwrap_err();
+        assert_eq!(
+            parse("inp w\nsub x 1"),
+            ParseError::UnknownInstruction {
+                line: 2,
+                name: "sub".to_string()
+            }
+        );
+        assert_eq!(
+            parse("add a 1"),
+            ParseError::InvalidRegister {
+                line: 1,
+                found: "a".to_string()
+            }
+        );
+        assert_eq!(
+            parse("inp w\n\nmul w 1.5").to_string(),
+            "Line 3: invalid operand '1.5'"
+        );
+        assert_eq!(
+            parse("inp w x").to_string(),
+            "Line 1: 'inp' takes 1 operands, found 2"
+        );
+        assert_eq!(
+            parse("eql x"),
+            ParseError::OperandCount {
+                line: 1,
+                name: "eql".to_string(),
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+}
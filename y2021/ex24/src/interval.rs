@@ -0,0 +1,184 @@
+use crate::{Alu, Instruction, Operand, Program};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub lo: i64,
+    pub hi: i64,
+}
+
+impl Interval {
+    pub const FULL: Interval = Interval {
+        lo: i64::MIN,
+        hi: i64::MAX,
+    };
+
+    pub fn new(lo: i64, hi: i64) -> Self {
+        debug_assert!(lo <= hi);
+        Interval { lo, hi }
+    }
+
+    pub fn exact(value: i64) -> Self {
+        Interval::new(value, value)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    // the ALU wraps around, so values that do not fit could end up anywhere
+    fn hull(values: impl IntoIterator<Item = i128>) -> Self {
+        let (lo, hi) = values
+            .into_iter()
+            .fold((i128::MAX, i128::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        match (i64::try_from(lo), i64::try_from(hi)) {
+            (Ok(lo), Ok(hi)) => Interval::new(lo, hi),
+            _ => Interval::FULL,
+        }
+    }
+
+    fn union(&self, other: &Interval) -> Self {
+        Interval::new(self.lo.min(other.lo), self.hi.max(other.hi))
+    }
+
+    fn corners(&self, other: &Interval) -> [(i128, i128); 4] {
+        let (a, b) = (
+            [self.lo as i128, self.hi as i128],
+            [other.lo as i128, other.hi as i128],
+        );
+        [(a[0], b[0]), (a[0], b[1]), (a[1], b[0]), (a[1], b[1])]
+    }
+
+    pub fn add(&self, other: &Interval) -> Self {
+        Interval::hull([
+            self.lo as i128 + other.lo as i128,
+            self.hi as i128 + other.hi as i128,
+        ])
+    }
+
+    pub fn mul(&self, other: &Interval) -> Self {
+        Interval::hull(self.corners(other).map(|(a, b)| a * b))
+    }
+
+    pub fn div(&self, other: &Interval) -> Option<Self> {
+        // division is monotonic on each side of 0, so the bounds come from the corners
+        let negative = (other.lo < 0).then(|| Interval::new(other.lo, other.hi.min(-1)));
+        let positive = (other.hi > 0).then(|| Interval::new(other.lo.max(1), other.hi));
+        [negative, positive]
+            .into_iter()
+            .flatten()
+            .map(|divisor| Interval::hull(self.corners(&divisor).map(|(a, b)| a / b)))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn rem(&self, other: &Interval) -> Option<Self> {
+        if self.hi < 0 || other.hi < 1 {
+            return None;
+        }
+        let (a, b) = (
+            Interval::new(self.lo.max(0), self.hi),
+            Interval::new(other.lo.max(1), other.hi),
+        );
+        if a.hi < b.lo {
+            Some(a)
+        } else {
+            Some(Interval::new(0, a.hi.min(b.hi - 1)))
+        }
+    }
+
+    pub fn eql(&self, other: &Interval) -> Self {
+        if self.lo == self.hi && self == other {
+            Interval::exact(1)
+        } else if self.hi < other.lo || other.hi < self.lo {
+            Interval::exact(0)
+        } else {
+            Interval::new(0, 1)
+        }
+    }
+}
+
+pub fn analyze(
+    program: &Program,
+    start: usize,
+    alu: &Alu,
+    input: Interval,
+) -> Option<[Interval; 4]> {
+    let mut registers = alu.registers.map(Interval::exact);
+    for instruction in program.instructions()[start..].iter() {
+        let value = |operand: Operand, registers: &[Interval; 4]| match operand {
+            Operand::Register(register) => registers[register.index()],
+            Operand::Value(value) => Interval::exact(value),
+        };
+        let (a, result) = match *instruction {
+            Instruction::Inp(a) => (a, input),
+            Instruction::Add(a, b) => (a, registers[a.index()].add(&value(b, &registers))),
+            Instruction::Mul(a, b) => (a, registers[a.index()].mul(&value(b, &registers))),
+            Instruction::Div(a, b) => (a, registers[a.index()].div(&value(b, &registers))?),
+            Instruction::Mod(a, b) => (a, registers[a.index()].rem(&value(b, &registers))?),
+            Instruction::Eql(a, b) => (a, registers[a.index()].eql(&value(b, &registers))),
+        };
+        registers[a.index()] = result;
+    }
+    Some(registers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Register;
+
+    #[test]
+    fn test_operations() {
+        let a = Interval::new(-7, 5);
+        let b = Interval::new(2, 3);
+        assert_eq!(a.add(&b), Interval::new(-5, 8));
+        assert_eq!(a.mul(&b), Interval::new(-21, 15));
+        assert_eq!(a.div(&b), Some(Interval::new(-3, 2)));
+        assert_eq!(a.div(&Interval::new(-1, 1)), Some(Interval::new(-7, 7)));
+        assert_eq!(a.div(&Interval::exact(0)), None);
+        assert_eq!(a.rem(&b), Some(Interval::new(0, 2)));
+        assert_eq!(
+            Interval::new(3, 9).rem(&Interval::exact(26)),
+            Some(Interval::new(3, 9))
+        );
+        assert_eq!(a.rem(&Interval::new(-4, 0)), None);
+        assert_eq!(a.eql(&b), Interval::new(0, 1));
+        assert_eq!(b.eql(&Interval::new(4, 9)), Interval::exact(0));
+        assert_eq!(
+            Interval::exact(4).eql(&Interval::exact(4)),
+            Interval::exact(1)
+        );
+
+        // overflowing could wrap around anywhere
+        let huge = Interval::new(0, i64::MAX);
+        assert_eq!(huge.mul(&b), Interval::FULL);
+        assert_eq!(
+            Interval::exact(i64::MIN).div(&Interval::exact(-1)),
+            Some(Interval::FULL)
+        );
+    }
+
+    #[test]
+    fn test_analyze() {
+        let program: Program = "inp w\nmul w 3\nadd z w\nmod z 4\ninp x\neql x w"
+            .parse()
+            .unwrap();
+        let registers = analyze(&program, 0, &Alu::default(), Interval::new(1, 9)).unwrap();
+        assert_eq!(registers[Register::W.index()], Interval::new(3, 27));
+        assert_eq!(registers[Register::X.index()], Interval::new(0, 1));
+        assert_eq!(registers[Register::Z.index()], Interval::new(0, 3));
+
+        // starting after the first input, from known registers
+        let alu = Alu {
+            registers: [12, 0, 0, 5],
+        };
+        let registers = analyze(&program, 4, &alu, Interval::new(1, 9)).unwrap();
+        assert_eq!(registers[Register::X.index()], Interval::exact(0));
+        assert_eq!(registers[Register::Z.index()], Interval::exact(5));
+
+        let crashing: Program = "inp w\nmul w -1\nmod w 2".parse().unwrap();
+        assert_eq!(
+            analyze(&crashing, 0, &Alu::default(), Interval::new(1, 9)),
+            None
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{analyze, Alu, Instruction, Interval, Program, Register};
+use std::collections::HashSet;
+
+const DIGITS: Interval = Interval { lo: 1, hi: 9 };
+
+struct Search<'a> {
+    program: &'a Program,
+    inputs: Vec<usize>,
+    digits: Vec<i64>,
+    dead: HashSet<(usize, Alu)>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, input: usize, alu: Alu) -> Option<Vec<i64>> {
+        if input == self.inputs.len() {
+            return (alu.get(Register::Z) == 0).then(Vec::new);
+        }
+        if self.dead.contains(&(input, alu)) {
+            return None;
+        }
+        let reachable = analyze(self.program, self.inputs[input], &alu, DIGITS)
+            .is_some_and(|registers| registers[Register::Z.index()].contains(0));
+
+        if reachable {
+            let end = self
+                .inputs
+                .get(input + 1)
+                .copied()
+                .unwrap_or(self.program.instructions().len());
+            for digit in self.digits.clone() {
+                let mut next = alu;
+                let ran = (self.inputs[input]..end).try_for_each(|index| {
+                    next.execute(self.program, index, &mut [digit].into_iter())
+                });
+                if ran.is_err() {
+                    continue;
+                }
+                if let Some(mut rest) = self.run(input + 1, next) {
+                    rest.insert(0, digit);
+                    return Some(rest);
+                }
+            }
+        }
+
+        self.dead.insert((input, alu));
+        None
+    }
+}
+
+fn search(program: &Program, digits: Vec<i64>) -> Option<u64> {
+    let inputs: Vec<usize> = program
+        .instructions()
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| matches!(instruction, Instruction::Inp(_)))
+        .map(|(index, _)| index)
+        .collect();
+    let first = *inputs.first()?;
+
+    let mut alu = Alu::default();
+    for index in 0..first {
+        alu.execute(program, index, &mut std::iter::empty()).ok()?;
+    }
+
+    let mut search = Search {
+        program,
+        inputs,
+        digits,
+        dead: HashSet::new(),
+    };
+    let model_number = search.run(0, alu)?;
+    Some(model_number.iter().fold(0, |acc, d| acc * 10 + *d as u64))
+}
+
+pub fn largest_model_number(program: &Program) -> Option<u64> {
+    search(program, (1..=9).rev().collect())
+}
+
+pub fn smallest_model_number(program: &Program) -> Option<u64> {
+    search(program, (1..=9).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_programs() {
+        // the second digit must be three times the first one
+        let three_times: Program = "inp z\ninp x\nmul z 3\neql z x\neql z 0".parse().unwrap();
+        assert_eq!(largest_model_number(&three_times), Some(39));
+        assert_eq!(smallest_model_number(&three_times), Some(13));
+        assert!(three_times.accepts(26));
+        assert!(!three_times.accepts(27));
+
+        // digits adding up to 20, with an instruction before the first input
+        let sum: Program = "add z -20\ninp w\nadd z w\ninp w\nadd z w\ninp w\nadd z w"
+            .parse()
+            .unwrap();
+        assert_eq!(largest_model_number(&sum), Some(992));
+        assert_eq!(smallest_model_number(&sum), Some(299));
+
+        // z can never be 0
+        let never: Program = "inp w\nadd z w".parse().unwrap();
+        assert_eq!(largest_model_number(&never), None);
+
+        // the only digit not crashing the program is 5
+        let crashing: Program = "inp w\nadd x 5\neql x w\nadd x -1\nmod x 2"
+            .parse()
+            .unwrap();
+        assert_eq!(largest_model_number(&crashing), Some(5));
+    }
+}
@@ -2,6 +2,7 @@ mod display;
 mod parser;
 mod program;
 use display::*;
+use ocr::read_grid;
 use parser::*;
 use program::*;
 
@@ -17,7 +18,7 @@ pub fn part1(input: &str) -> i32 {
     values.iter().map(|cpu| cpu.cycle * cpu.x).sum::<i32>()
 }
 
-pub fn part2(input: &str) -> String {
+pub fn render(input: &str) -> String {
     let mut display = Display::new();
     display.set_pixel(Pixel::On); // first pixel is always on (cycle 1)
     let cmds = input.lines().map(|line| parse_line(line).unwrap().1);
@@ -35,6 +36,10 @@ pub fn part2(input: &str) -> String {
     display.to_string()
 }
 
+pub fn part2(input: &str) -> String {
+    read_grid(&render(input)).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,7 +57,7 @@ mod tests {
     }
 
     #[test]
-    fn test_part2_example() {
+    fn test_render_example() {
         let expected = "##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
 ####....####....####....####....####....
@@ -60,7 +65,7 @@ mod tests {
 ######......######......######......####
 #######.......#######.......#######.....
 ";
-        assert_eq!(part2(SAMPLE_INPUT), expected.to_string());
+        assert_eq!(render(SAMPLE_INPUT), expected.to_string());
     }
 
     #[test]
@@ -72,6 +77,7 @@ mod tests {
 #....#..#.#....#....#....#..#.#..#.#....
 ####.#..#.####.#....####.#..#..##..####.
 ";
-        assert_eq!(part2(INPUT), expected.to_string());
+        assert_eq!(render(INPUT), expected.to_string());
+        assert_eq!(part2(INPUT), "EHZFZHCZ");
     }
 }